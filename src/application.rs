@@ -17,25 +17,38 @@
 |                                                                              |
 \******************************************************************************/
 
-use std::path::PathBuf;
+use crate::{
+    Action, Conflict, Dictionary, Digest, ExtractOptions, Locking, Manifest,
+    Overwrite, Plan,
+};
+use clap::CommandFactory;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use sysexits::{ExitCode, Result};
 
-/// Interact with Brotli, TAR, TAR.BR, and TBR archives.
+/// Interact with ar, Brotli, cpio, TAR, TAR.BR, TBR, and ZIP archives.
 #[derive(clap::Parser, Clone)]
+#[command(name = "tbr")]
 pub struct Application {
-    /// The operation to perform on the considered archive.
-    mode: Mode,
-
-    /// The archive to interact with.
-    archive: PathBuf,
+    #[command(subcommand)]
+    command: Command,
 
-    /// The file(s) to add to the considered archive.
-    files: Vec<PathBuf>,
+    /// Report more details:  `-v` for the files being written or removed,
+    /// `-vv` for each entry, as well.
+    #[arg(action = clap::ArgAction::Count, global = true, long, short)]
+    verbose: u8,
 
-    /// The directory to unpack the archive's files into; defaulting to the
-    /// current working directory, if omitted.
-    #[arg(long, short)]
-    destination: Option<PathBuf>,
+    /// Report less details:  `-q` for errors only, `-qq` for nothing at all.
+    #[arg(
+        action = clap::ArgAction::Count,
+        conflicts_with = "verbose",
+        global = true,
+        long,
+        short
+    )]
+    quiet: u8,
 }
 
 impl Application {
@@ -45,46 +58,946 @@ impl Application {
     ///
     /// See [`sysexits::ExitCode`].
     pub fn main(&self) -> Result<()> {
-        self.wrap().main()
+        match &self.command {
+            Command::Archive(operation) => operation.wrap().main(),
+            Command::Completions { shell } => {
+                clap_complete::generate(
+                    *shell,
+                    &mut Self::command(),
+                    "tbr",
+                    &mut std::io::stdout(),
+                );
+                Ok(())
+            }
+            Command::Manual { directory } => {
+                if let Some(directory) = directory {
+                    clap_mangen::generate_to(Self::manual(), directory)?;
+                } else {
+                    clap_mangen::Man::new(Self::manual())
+                        .render(&mut std::io::stdout())?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn manual() -> clap::Command {
+        let mut command = Self::command();
+        let names = command
+            .get_subcommands()
+            .map(|c| c.get_name().to_string())
+            .collect::<Vec<_>>();
+
+        for name in names {
+            command = command.mut_subcommand(name, |c| {
+                let aliases = c.get_visible_aliases().collect::<Vec<_>>();
+
+                if aliases.is_empty() {
+                    return c;
+                }
+
+                let about = format!(
+                    "{} [aliases: {}]",
+                    c.get_about().map(ToString::to_string).unwrap_or_default(),
+                    aliases.join(", ")
+                );
+
+                c.about(about)
+            });
+        }
+
+        command
+    }
+
+    /// The most detailed level of log messages to report, according to the
+    /// verbosity flags.
+    ///
+    /// By default, errors and warnings will be reported.
+    #[must_use]
+    pub const fn verbosity(&self) -> log::LevelFilter {
+        match (self.quiet, self.verbose) {
+            (0, 0) => log::LevelFilter::Warn,
+            (0, 1) => log::LevelFilter::Info,
+            (0, 2) => log::LevelFilter::Debug,
+            (0, _) => log::LevelFilter::Trace,
+            (1, _) => log::LevelFilter::Error,
+            _ => log::LevelFilter::Off,
+        }
+    }
+}
+
+/// The archive to interact with, together with the dictionary to read and
+/// write it with.
+#[derive(clap::Args, Clone)]
+struct ArchiveArgs {
+    /// The archive to interact with.
+    archive: PathBuf,
+
+    /// The custom Brotli dictionary to compress and decompress with.
+    #[arg(long)]
+    dictionary: Option<PathBuf>,
+}
+
+impl ArchiveArgs {
+    fn apply(self, settings: &mut Settings) -> PathBuf {
+        settings.dictionary = self.dictionary;
+        self.archive
+    }
+}
+
+/// The commands.
+#[derive(clap::Subcommand, Clone)]
+enum Command {
+    #[command(flatten)]
+    Archive(Operation),
+
+    /// Print the completions for the given shell.
+    Completions {
+        /// The shell to complete the command line for.
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the manual page in roff format.
+    #[command(visible_alias = "man")]
+    Manual {
+        /// The directory to write the manual pages of all commands to;
+        /// printing the main page only, if omitted.
+        directory: Option<PathBuf>,
+    },
+}
+
+/// The metadata to restore when extracting an archive.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(clap::Args, Clone)]
+struct ExtractArgs {
+    /// The directory to unpack the archive's files into; defaulting to the
+    /// current working directory, if omitted.
+    #[arg(long, short)]
+    destination: Option<PathBuf>,
+
+    /// Whether to restore extended attributes, including POSIX ACLs and file
+    /// capabilities, from TAR archives.
+    #[arg(long)]
+    xattrs: bool,
+
+    /// Whether to restore the owners of extracted files; the default for the
+    /// superuser.
+    #[arg(long, overrides_with = "no_same_owner")]
+    same_owner: bool,
+
+    /// Whether to extract files as the current user; the default for all other
+    /// users.
+    #[arg(long, overrides_with = "same_owner")]
+    no_same_owner: bool,
+
+    /// Whether to look up the owners of extracted files by their numeric IDs
+    /// only, instead of their names.
+    #[arg(long)]
+    numeric_owner: bool,
+
+    /// Whether to restore all permission bits of extracted files, including
+    /// the setuid, setgid, and sticky bits; the default for the superuser.
+    #[arg(long, overrides_with = "no_same_permissions", short = 'p')]
+    same_permissions: bool,
+
+    /// Whether to apply the umask to the permission bits of extracted files;
+    /// the default for all other users.
+    #[arg(long, overrides_with = "same_permissions")]
+    no_same_permissions: bool,
+
+    /// The permission bits to clear from extracted files, in octal notation;
+    /// defaulting to the process' umask unless restoring all permission bits.
+    #[arg(long, value_parser = umask)]
+    umask: Option<u32>,
+
+    /// Whether to set the modification times of extracted files to the time of
+    /// extraction.
+    #[arg(long)]
+    touch: bool,
+
+    /// How to treat files which already exist:  `replace`, `skip`,
+    /// `keep-newer`, `fail`, `rename`, or `ask`.
+    #[arg(default_value = "replace", long)]
+    overwrite: Overwrite,
+
+    /// Whether to only report the files which would be extracted, replaced,
+    /// or skipped, without changing the file system.
+    #[arg(long, short = 'n')]
+    dry_run: bool,
+}
+
+impl ExtractArgs {
+    fn apply(self, settings: &mut Settings) {
+        settings.destination = self.destination;
+        settings.dry_run = self.dry_run;
+        settings.no_same_owner = self.no_same_owner;
+        settings.no_same_permissions = self.no_same_permissions;
+        settings.numeric_owner = self.numeric_owner;
+        settings.overwrite = self.overwrite;
+        settings.same_owner = self.same_owner;
+        settings.same_permissions = self.same_permissions;
+        settings.touch = self.touch;
+        settings.umask = self.umask;
+        settings.xattrs = self.xattrs;
+    }
+}
+
+/// The checksum manifest to write for or check an archive against.
+#[derive(clap::Args, Clone)]
+struct ManifestArgs {
+    /// The checksum manifest to write for the archive or, when testing it, to
+    /// check the archive against.
+    #[arg(long, short)]
+    manifest: Option<PathBuf>,
+
+    /// The digest algorithm of the checksum manifest.
+    #[arg(default_value = "sha256", long)]
+    digest: Digest,
+}
+
+impl ManifestArgs {
+    fn apply(self, settings: &mut Settings) {
+        settings.digest = self.digest;
+        settings.manifest = self.manifest;
+    }
+}
+
+/// How to modify an archive which other processes might modify, as well.
+#[derive(clap::Args, Clone)]
+struct ModifyArgs {
+    /// Whether to wait for the archive if another process is modifying it;
+    /// optionally, at most the given number of seconds.  This is the default.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        overrides_with = "no_wait",
+        require_equals = true,
+        value_name = "SECONDS"
+    )]
+    #[allow(clippy::option_option)]
+    wait: Option<Option<u64>>,
+
+    /// Whether to fail immediately if another process is modifying the
+    /// archive.
+    #[arg(long, overrides_with = "wait")]
+    no_wait: bool,
+
+    /// Whether to only report the entries which would be added, replaced, or
+    /// deleted, without changing the file system.
+    #[arg(long, short = 'n')]
+    dry_run: bool,
+}
+
+impl ModifyArgs {
+    const fn apply(self, settings: &mut Settings) {
+        settings.dry_run = self.dry_run;
+        settings.no_wait = self.no_wait;
+        settings.wait = self.wait;
+    }
+}
+
+/// The operations on an archive.
+#[derive(clap::Subcommand, Clone)]
+enum Operation {
+    /// Add the given files to the archive, creating it if necessary.
+    #[command(visible_aliases = ["add", "compress", "edit", "update"])]
+    Create {
+        #[command(flatten)]
+        archive: ArchiveArgs,
+
+        /// The files to add.
+        files: Vec<PathBuf>,
+
+        #[command(flatten)]
+        rewrite: RewriteArgs,
+
+        #[command(flatten)]
+        manifest: ManifestArgs,
+    },
+
+    /// Show the archive's contents.
+    #[command(visible_aliases = ["content", "info", "show"])]
+    List {
+        #[command(flatten)]
+        archive: ArchiveArgs,
+
+        #[command(flatten)]
+        manifest: ManifestArgs,
+    },
+
+    /// Extract the archive's contents.
+    #[command(visible_aliases = ["decompress", "uncompress", "unpack"])]
+    Extract {
+        #[command(flatten)]
+        archive: ArchiveArgs,
+
+        /// The members to extract; all of them, if omitted.
+        members: Vec<PathBuf>,
+
+        #[command(flatten)]
+        extract: ExtractArgs,
+    },
+
+    /// Remove the archive from the file system.
+    #[command(visible_alias = "remove")]
+    Delete {
+        /// The archive to remove, including all of its volumes.
+        archive: PathBuf,
+
+        #[command(flatten)]
+        modify: ModifyArgs,
+    },
+
+    /// Test the archive's integrity.
+    #[command(visible_aliases = ["check", "verify"])]
+    Test {
+        #[command(flatten)]
+        archive: ArchiveArgs,
+
+        #[command(flatten)]
+        manifest: ManifestArgs,
+
+        /// The directory to check against the checksum manifest instead of the
+        /// archive.
+        #[arg(long, requires = "manifest", short)]
+        destination: Option<PathBuf>,
+    },
+
+    /// Print the content of a single member of the archive.
+    #[command(visible_alias = "print")]
+    Cat {
+        #[command(flatten)]
+        archive: ArchiveArgs,
+
+        /// The member to print.
+        member: PathBuf,
+    },
+
+    /// Compare the archive with another archive or a directory.
+    #[command(visible_alias = "compare")]
+    Diff {
+        #[command(flatten)]
+        archive: ArchiveArgs,
+
+        /// The archive or directory to compare with.
+        other: PathBuf,
+
+        /// Whether to print the comparison as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Convert the archive into a TAR archive of another compression.
+    #[command(visible_aliases = ["recompress", "repack"])]
+    Convert {
+        #[command(flatten)]
+        archive: ArchiveArgs,
+
+        /// The TAR archive to write.
+        target: PathBuf,
+
+        #[command(flatten)]
+        rewrite: RewriteArgs,
+    },
+
+    /// Merge other archives into the archive, keeping its existing members.
+    #[command(visible_aliases = ["combine", "concat"])]
+    Merge {
+        #[command(flatten)]
+        archive: ArchiveArgs,
+
+        /// The archives to merge.
+        #[arg(required = true)]
+        sources: Vec<PathBuf>,
+
+        /// How to resolve duplicate member paths:  `error`, `first`, or
+        /// `last`.
+        #[arg(default_value = "error", long)]
+        conflict: Conflict,
+
+        #[command(flatten)]
+        rewrite: RewriteArgs,
+    },
+
+    /// Rename members of the archive.
+    #[command(visible_aliases = ["move", "mv"])]
+    Rename {
+        #[command(flatten)]
+        archive: ArchiveArgs,
+
+        /// The pairs of old and new member paths.
+        #[arg(required = true)]
+        pairs: Vec<PathBuf>,
+
+        #[command(flatten)]
+        rewrite: RewriteArgs,
+    },
+
+    /// Train a Brotli dictionary on the given sample files.
+    #[command(visible_alias = "train-dictionary")]
+    Train {
+        /// The dictionary to write.
+        dictionary: PathBuf,
+
+        /// The sample files to train the dictionary on.
+        #[arg(required = true)]
+        samples: Vec<PathBuf>,
+
+        /// The maximum size of the dictionary in bytes.
+        #[arg(default_value = "112640", long)]
+        dictionary_size: usize,
+
+        /// Whether to only report the dictionary which would be written,
+        /// without changing the file system.
+        #[arg(long, short = 'n')]
+        dry_run: bool,
+    },
+}
+
+impl Operation {
+    fn settings(self) -> (Mode, PathBuf, Settings) {
+        let mut settings = Settings::default();
+        let (mode, archive) = match self {
+            Self::Cat { archive, member } => {
+                settings.files = vec![member];
+                (Mode::Output, archive.apply(&mut settings))
+            }
+            Self::Convert {
+                archive,
+                target,
+                rewrite,
+            } => {
+                settings.files = vec![target];
+                rewrite.apply(&mut settings);
+                (Mode::Conversion, archive.apply(&mut settings))
+            }
+            Self::Create {
+                archive,
+                files,
+                rewrite,
+                manifest,
+            } => {
+                settings.files = files;
+                manifest.apply(&mut settings);
+                rewrite.apply(&mut settings);
+                (Mode::Update, archive.apply(&mut settings))
+            }
+            Self::Delete { archive, modify } => {
+                modify.apply(&mut settings);
+                (Mode::Removal, archive)
+            }
+            Self::Diff {
+                archive,
+                other,
+                json,
+            } => {
+                settings.files = vec![other];
+                settings.json = json;
+                (Mode::Difference, archive.apply(&mut settings))
+            }
+            Self::Extract {
+                archive,
+                members,
+                extract,
+            } => {
+                settings.files = members;
+                extract.apply(&mut settings);
+                (Mode::Extraction, archive.apply(&mut settings))
+            }
+            Self::List { archive, manifest } => {
+                manifest.apply(&mut settings);
+                (Mode::Content, archive.apply(&mut settings))
+            }
+            Self::Merge {
+                archive,
+                sources,
+                conflict,
+                rewrite,
+            } => {
+                settings.conflict = conflict;
+                settings.files = sources;
+                rewrite.apply(&mut settings);
+                (Mode::Merge, archive.apply(&mut settings))
+            }
+            Self::Rename {
+                archive,
+                pairs,
+                rewrite,
+            } => {
+                settings.files = pairs;
+                rewrite.apply(&mut settings);
+                (Mode::Renaming, archive.apply(&mut settings))
+            }
+            Self::Test {
+                archive,
+                manifest,
+                destination,
+            } => {
+                settings.destination = destination;
+                manifest.apply(&mut settings);
+                (Mode::Test, archive.apply(&mut settings))
+            }
+            Self::Train {
+                dictionary,
+                samples,
+                dictionary_size,
+                dry_run,
+            } => {
+                settings.dictionary_size = dictionary_size;
+                settings.dry_run = dry_run;
+                settings.files = samples;
+                (Mode::Training, dictionary)
+            }
+        };
+
+        (mode, archive, settings)
     }
 
     fn wrap(&self) -> Logic {
+        let (mode, archive, settings) = self.clone().settings();
+
         Logic {
-            cli: self.clone(),
+            archive,
+            dictionary: None,
+            mode,
             paths: Vec::new(),
+            settings,
+        }
+    }
+}
+
+/// How to rewrite an archive which other processes might modify, as well.
+#[derive(clap::Args, Clone)]
+struct RewriteArgs {
+    #[command(flatten)]
+    write: WriteArgs,
+
+    #[command(flatten)]
+    modify: ModifyArgs,
+}
+
+impl RewriteArgs {
+    const fn apply(self, settings: &mut Settings) {
+        self.write.apply(settings);
+        self.modify.apply(settings);
+    }
+}
+
+/// The settings of all operations, with the defaults of those which the
+/// current operation does not offer.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone)]
+struct Settings {
+    conflict: Conflict,
+    destination: Option<PathBuf>,
+    dictionary: Option<PathBuf>,
+    dictionary_size: usize,
+    digest: Digest,
+    dry_run: bool,
+    files: Vec<PathBuf>,
+    json: bool,
+    level: Option<i32>,
+    long: Option<u32>,
+    manifest: Option<PathBuf>,
+    no_same_owner: bool,
+    no_same_permissions: bool,
+    no_wait: bool,
+    numeric_owner: bool,
+    overwrite: Overwrite,
+    same_owner: bool,
+    same_permissions: bool,
+    seekable: bool,
+    touch: bool,
+    umask: Option<u32>,
+    volume_size: Option<u64>,
+    #[allow(clippy::option_option)]
+    wait: Option<Option<u64>>,
+    xattrs: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            conflict: Conflict::default(),
+            destination: None,
+            dictionary: None,
+            dictionary_size: 112_640,
+            digest: Digest::Sha256,
+            dry_run: false,
+            files: Vec::new(),
+            json: false,
+            level: None,
+            long: None,
+            manifest: None,
+            no_same_owner: false,
+            no_same_permissions: false,
+            no_wait: false,
+            numeric_owner: false,
+            overwrite: Overwrite::default(),
+            same_owner: false,
+            same_permissions: false,
+            seekable: false,
+            touch: false,
+            umask: None,
+            volume_size: None,
+            wait: None,
+            xattrs: false,
         }
     }
 }
 
+/// The options to write TAR archives with.
+#[derive(clap::Args, Clone)]
+struct WriteArgs {
+    /// The compression level to use when writing compressed TAR or ZIP
+    /// archives.
+    #[arg(allow_negative_numbers = true, long, short)]
+    level: Option<i32>,
+
+    /// Enable long distance matching for Zstandard compressed TAR archives
+    /// with the given window size, expressed as a power of two.
+    #[arg(
+        default_missing_value = "27",
+        long,
+        num_args = 0..=1,
+        require_equals = true
+    )]
+    long: Option<u32>,
+
+    /// Whether to write Brotli compressed TAR archives in the seekable layout
+    /// such that single members can be accessed without decoding the entire
+    /// archive.
+    #[arg(long)]
+    seekable: bool,
+
+    /// Split written TAR archives into numbered volumes of the given size,
+    /// such as `2G`, `500MB`, or `65536`; defaulting to the size of the first
+    /// volume when rewriting a split archive.
+    #[arg(long, value_parser = volume_size)]
+    volume_size: Option<u64>,
+
+    /// Whether to capture extended attributes, including POSIX ACLs and file
+    /// capabilities, when writing TAR archives.
+    #[arg(long)]
+    xattrs: bool,
+}
+
+impl WriteArgs {
+    const fn apply(self, settings: &mut Settings) {
+        settings.level = self.level;
+        settings.long = self.long;
+        settings.seekable = self.seekable;
+        settings.volume_size = self.volume_size;
+        settings.xattrs = self.xattrs;
+    }
+}
+
 struct Logic {
-    cli: Application,
+    archive: PathBuf,
+    dictionary: Option<Dictionary>,
+    mode: Mode,
     paths: Vec<PathBuf>,
+    settings: Settings,
 }
 
 impl Logic {
     fn main(&mut self) -> Result<()> {
         self.resolve_files()?;
 
-        self.cli.archive.extension().map_or_else(
-            || {
-                eprintln!(
-                    "Please specify the archive to work on with its extension."
+        if self.mode == Mode::Training && self.settings.dry_run {
+            print!("{}", Plan::write(&self.archive));
+            return Ok(());
+        }
+
+        if self.mode == Mode::Training {
+            return Dictionary::train(
+                &self.paths,
+                self.settings.dictionary_size,
+            )?
+            .write(&self.archive);
+        }
+
+        if let Some(dictionary) = &self.settings.dictionary {
+            self.dictionary = Some(Dictionary::read(dictionary)?);
+        }
+
+        match Kind::detect(&self.archive)? {
+            Kind::Ar => self.ar_archive(),
+            Kind::Brotli => self.brotli_archive(),
+            Kind::Cpio => self.cpio_archive(),
+            Kind::Tar => self.tar_archive(),
+            #[cfg(feature = "zip")]
+            Kind::Zip => self.zip_archive(),
+        }
+    }
+
+    fn ar_archive(&self) -> Result<()> {
+        let ar = crate::Ar::new(&self.archive)
+            .with_extract_options(self.extract_options());
+
+        self.check_options("ar", &[])?;
+
+        match self.mode {
+            Mode::Content => {
+                for path in ar.list()? {
+                    println!("{}", path.display());
+                }
+
+                Ok(())
+            }
+            Mode::Conversion => ar.convert(&self.conversion_target()?),
+            Mode::Extraction if self.settings.files.is_empty() => {
+                ar.extract(self.destination())
+            }
+            Mode::Extraction => {
+                ar.extract_members(self.destination(), &self.settings.files)
+            }
+            Mode::Removal => ar.remove(),
+            mode @ (Mode::Difference
+            | Mode::Merge
+            | Mode::Output
+            | Mode::Renaming
+            | Mode::Test
+            | Mode::Training
+            | Mode::Update) => {
+                log::error!(
+                    "The {mode} is not supported for ar archives, yet."
                 );
                 Err(ExitCode::Usage)
-            },
-            |e| {
-                if e.to_str() == Some("tar") {
-                    self.tar_archive()
+            }
+        }
+    }
+
+    fn brotli_archive(&self) -> Result<()> {
+        let mut brotli = crate::Brotli::new(&self.archive)
+            .with_extract_options(self.extract_options())
+            .with_locking(self.locking());
+
+        self.check_options("Brotli", &["--dictionary"])?;
+
+        if let Some(dictionary) = &self.dictionary {
+            brotli = brotli.with_dictionary(dictionary.clone());
+        }
+
+        match self.mode {
+            Mode::Test => brotli.test(),
+            mode => {
+                log::error!(
+                    "The {mode} is not supported for Brotli archives, yet."
+                );
+                Err(ExitCode::Usage)
+            }
+        }
+    }
+
+    fn check_options(&self, kind: &str, supported: &[&str]) -> Result<()> {
+        // Conversions write TAR archives which support all write options.
+        let converting = self.mode == Mode::Conversion;
+        let unsupported = [
+            (
+                "--dictionary",
+                !converting && self.settings.dictionary.is_some(),
+            ),
+            ("--dry-run", self.settings.dry_run && self.mutating()),
+            ("--level", !converting && self.settings.level.is_some()),
+            ("--long", !converting && self.settings.long.is_some()),
+            ("--manifest", self.settings.manifest.is_some()),
+            ("--seekable", !converting && self.settings.seekable),
+            (
+                "--volume-size",
+                !converting && self.settings.volume_size.is_some(),
+            ),
+            ("--xattrs", self.settings.xattrs),
+        ]
+        .into_iter()
+        .filter(|(option, set)| *set && !supported.contains(option))
+        .map(|(option, _)| option)
+        .collect::<Vec<_>>();
+
+        for option in &unsupported {
+            log::error!(
+                "The option {option} is not supported for {kind} archives, \
+                 yet."
+            );
+        }
+
+        if unsupported.is_empty() {
+            Ok(())
+        } else {
+            Err(ExitCode::Usage)
+        }
+    }
+
+    fn conversion_target(&self) -> Result<crate::Tar> {
+        let [target] = self.settings.files.as_slice() else {
+            log::error!("Please specify exactly one target archive.");
+            return Err(ExitCode::Usage);
+        };
+
+        if Kind::detect(target)? != Kind::Tar {
+            log::error!("The target needs to be a TAR archive.");
+            return Err(ExitCode::Usage);
+        }
+
+        Ok(self.tar(target))
+    }
+
+    fn cpio_archive(&self) -> Result<()> {
+        let cpio = crate::Cpio::new(&self.archive)
+            .with_extract_options(self.extract_options());
+
+        self.check_options("cpio", &[])?;
+
+        match self.mode {
+            Mode::Content => {
+                for path in cpio.list()? {
+                    println!("{}", path.display());
+                }
+
+                Ok(())
+            }
+            Mode::Conversion => cpio.convert(&self.conversion_target()?),
+            Mode::Extraction if self.settings.files.is_empty() => {
+                cpio.extract(self.destination())
+            }
+            Mode::Extraction => {
+                cpio.extract_members(self.destination(), &self.settings.files)
+            }
+            Mode::Removal => cpio.remove(),
+            mode @ (Mode::Difference
+            | Mode::Merge
+            | Mode::Output
+            | Mode::Renaming
+            | Mode::Test
+            | Mode::Training
+            | Mode::Update) => {
+                log::error!(
+                    "The {mode} is not supported for cpio archives, yet."
+                );
+                Err(ExitCode::Usage)
+            }
+        }
+    }
+
+    fn destination(&self) -> &Path {
+        self.settings
+            .destination
+            .as_ref()
+            .map_or_else(|| Path::new("."), PathBuf::as_path)
+    }
+
+    fn emit_manifest(&self, tar: &crate::Tar) -> Result<()> {
+        self.settings.manifest.as_ref().map_or(Ok(()), |m| {
+            if self.settings.dry_run {
+                print!("{}", Plan::write(m));
+                Ok(())
+            } else {
+                tar.manifest(self.settings.digest)?.write(m)
+            }
+        })
+    }
+
+    fn extract_options(&self) -> ExtractOptions {
+        let permissions = self.settings.same_permissions
+            || !self.settings.no_same_permissions && superuser();
+
+        ExtractOptions::default()
+            .with_confirm(ask)
+            .with_mask(self.settings.umask.unwrap_or_else(|| {
+                if permissions {
+                    0
                 } else {
-                    eprintln!("This archive type is not supported.");
-                    Err(ExitCode::Usage)
+                    process_umask()
                 }
-            },
+            }))
+            .with_mtime(!self.settings.touch)
+            .with_numeric_owner(self.settings.numeric_owner)
+            .with_overwrite(self.settings.overwrite)
+            .with_ownership(
+                self.settings.same_owner
+                    || !self.settings.no_same_owner && superuser(),
+            )
+            .with_permissions(permissions)
+    }
+
+    const fn locking(&self) -> Locking {
+        if self.settings.no_wait {
+            Locking::NoWait
+        } else if let Some(Some(seconds)) = self.settings.wait {
+            Locking::Timeout(Duration::from_secs(seconds))
+        } else {
+            Locking::Wait
+        }
+    }
+
+    const fn mutating(&self) -> bool {
+        matches!(
+            self.mode,
+            Mode::Conversion
+                | Mode::Extraction
+                | Mode::Merge
+                | Mode::Removal
+                | Mode::Renaming
+                | Mode::Training
+                | Mode::Update
         )
     }
 
+    fn merge_sources(&self, directory: &Path) -> Result<Vec<crate::Tar>> {
+        if self.paths.is_empty() {
+            log::error!("Please specify the archives to merge.");
+            return Err(ExitCode::Usage);
+        }
+
+        let mut result = Vec::new();
+
+        for (index, path) in self.paths.iter().enumerate() {
+            let target =
+                crate::Tar::new(directory.join(format!("{index}.tar")));
+
+            result.push(match Kind::detect(path)? {
+                Kind::Ar => {
+                    crate::Ar::new(path).convert(&target)?;
+                    target
+                }
+                Kind::Brotli => {
+                    log::error!("{}:  not an archive", path.display());
+                    return Err(ExitCode::Usage);
+                }
+                Kind::Cpio => {
+                    crate::Cpio::new(path).convert(&target)?;
+                    target
+                }
+                Kind::Tar => self.tar(path),
+                #[cfg(feature = "zip")]
+                Kind::Zip => {
+                    crate::Zip::new(path).convert(&target)?;
+                    target
+                }
+            });
+        }
+
+        Ok(result)
+    }
+
+    fn renaming(&self) -> Result<Vec<(&PathBuf, &PathBuf)>> {
+        if self.settings.files.is_empty() || self.settings.files.len() % 2 == 1
+        {
+            log::error!("Please specify pairs of old and new paths.");
+            return Err(ExitCode::Usage);
+        }
+
+        Ok(self
+            .settings
+            .files
+            .chunks_exact(2)
+            .map(|p| (&p[0], &p[1]))
+            .collect())
+    }
+
     fn resolve_files(&mut self) -> Result<()> {
-        for file in &self.cli.files {
+        for file in &self.settings.files {
             for path in
                 glob::glob(file.as_path().to_str().ok_or(ExitCode::DataErr)?)
                     .map_or(Err(ExitCode::DataErr), Ok)?
@@ -97,24 +1010,275 @@ impl Logic {
     }
 
     fn tar_archive(&self) -> Result<()> {
-        let tar = crate::Tar::new(&self.cli.archive);
+        let tar = self.tar(&self.archive);
+
+        if self.settings.dry_run && self.mutating() {
+            let plan = self.tar_plan(&tar)?;
+            let mut conflicts = plan
+                .actions()
+                .iter()
+                .filter(|a| matches!(a, Action::Fail(_)))
+                .peekable();
+
+            print!("{plan}");
+
+            if conflicts.peek().is_some() {
+                for action in conflicts {
+                    log::error!("{}:  already exists", action.path().display());
+                }
 
-        match self.cli.mode {
+                return Err(ExitCode::CantCreat);
+            }
+
+            return if self.mode == Mode::Update {
+                self.emit_manifest(&tar)
+            } else {
+                Ok(())
+            };
+        }
+
+        match self.mode {
             Mode::Content => {
                 for path in tar.list()? {
                     println!("{}", path.display());
                 }
 
+                self.emit_manifest(&tar)
+            }
+            Mode::Conversion => tar.convert(&self.conversion_target()?),
+            Mode::Difference => {
+                let [other] = self.paths.as_slice() else {
+                    log::error!(
+                        "Please specify exactly one archive or directory to \
+                         compare with."
+                    );
+                    return Err(ExitCode::Usage);
+                };
+                let difference = crate::Difference::new(&self.archive, other)?;
+
+                if self.settings.json {
+                    println!("{}", difference.to_json());
+                } else {
+                    print!("{difference}");
+                }
+
                 Ok(())
             }
-            Mode::Extraction => tar.extract(
-                self.cli
-                    .destination
-                    .as_ref()
-                    .map_or(".", |d| d.as_path().to_str().map_or(".", |s| s)),
-            ),
+            Mode::Extraction if self.settings.files.is_empty() => {
+                tar.extract(self.destination())
+            }
+            Mode::Extraction => {
+                tar.extract_members(self.destination(), &self.settings.files)
+            }
+            Mode::Merge => {
+                let directory = tempfile::tempdir()
+                    .map_or(Err(ExitCode::Unavailable), Ok)?;
+
+                tar.merge(
+                    &self.merge_sources(directory.path())?,
+                    self.settings.conflict,
+                )
+            }
+            Mode::Output => {
+                let [member] = self.settings.files.as_slice() else {
+                    log::error!("Please specify exactly one member to print.");
+                    return Err(ExitCode::Usage);
+                };
+
+                tar.cat(member, &mut std::io::stdout().lock())
+            }
             Mode::Removal => tar.remove(),
-            Mode::Update => tar.add_files(&self.paths),
+            Mode::Renaming => tar.rename(&self.renaming()?),
+            Mode::Test => {
+                tar.test()?;
+                self.verify_manifest(&tar)
+            }
+            Mode::Training => {
+                log::error!(
+                    "The {} is not supported for TAR archives.",
+                    Mode::Training
+                );
+                Err(ExitCode::Usage)
+            }
+            Mode::Update => {
+                tar.add_files(&self.paths)?;
+                self.emit_manifest(&tar)
+            }
+        }
+    }
+
+    fn tar_plan(&self, tar: &crate::Tar) -> Result<Plan> {
+        match self.mode {
+            Mode::Conversion => Ok(self.conversion_target()?.plan_write()),
+            Mode::Extraction if self.settings.files.is_empty() => {
+                tar.plan_extract(self.destination())
+            }
+            Mode::Extraction => tar
+                .plan_extract_members(self.destination(), &self.settings.files),
+            Mode::Merge => {
+                let directory = tempfile::tempdir()
+                    .map_or(Err(ExitCode::Unavailable), Ok)?;
+
+                tar.plan_merge(
+                    &self.merge_sources(directory.path())?,
+                    self.settings.conflict,
+                )
+            }
+            Mode::Removal => tar.plan_remove(),
+            Mode::Renaming => tar.plan_rename(&self.renaming()?),
+            Mode::Update => tar.plan_add_files(&self.paths),
+            Mode::Content
+            | Mode::Difference
+            | Mode::Output
+            | Mode::Test
+            | Mode::Training => Ok(tar.plan_write()),
+        }
+    }
+
+    fn tar(&self, path: &Path) -> crate::Tar {
+        let mut tar = crate::Tar::new(path);
+
+        if let Some(level) = self.settings.level {
+            tar = tar.with_level(level);
+        }
+
+        if let Some(window) = self.settings.long {
+            tar = tar.with_window(window);
+        }
+
+        if let Some(dictionary) = &self.dictionary {
+            tar = tar.with_dictionary(dictionary.clone());
+        }
+
+        if self.settings.seekable {
+            tar = tar.with_seekable();
+        }
+
+        if let Some(size) = self.settings.volume_size {
+            tar = tar.with_volume_size(size);
+        }
+
+        if self.settings.xattrs {
+            tar = tar.with_xattrs();
+        }
+
+        tar.with_extract_options(self.extract_options())
+            .with_locking(self.locking())
+    }
+
+    fn verify_manifest(&self, tar: &crate::Tar) -> Result<()> {
+        self.settings.manifest.as_ref().map_or(Ok(()), |m| {
+            let manifest = Manifest::read(m, self.settings.digest)?;
+
+            self.settings.destination.as_ref().map_or_else(
+                || manifest.verify(&tar.manifest(self.settings.digest)?),
+                |d| manifest.verify_directory(d),
+            )
+        })
+    }
+
+    #[cfg(feature = "zip")]
+    fn zip_archive(&self) -> Result<()> {
+        let mut zip = crate::Zip::new(&self.archive)
+            .with_extract_options(self.extract_options())
+            .with_locking(self.locking());
+
+        self.check_options(
+            "ZIP",
+            if self.mode == Mode::Update {
+                &["--level"]
+            } else {
+                &[]
+            },
+        )?;
+
+        if let Some(level) = self.settings.level {
+            zip = zip.with_level(level);
+        }
+
+        match self.mode {
+            Mode::Content => {
+                for path in zip.list()? {
+                    println!("{}", path.display());
+                }
+
+                Ok(())
+            }
+            Mode::Conversion => zip.convert(&self.conversion_target()?),
+            Mode::Extraction if self.settings.files.is_empty() => {
+                zip.extract(self.destination())
+            }
+            Mode::Extraction => {
+                zip.extract_members(self.destination(), &self.settings.files)
+            }
+            Mode::Removal => zip.remove(),
+            Mode::Test => zip.test(),
+            Mode::Update => zip.add_files(&self.paths),
+            mode @ (Mode::Difference
+            | Mode::Merge
+            | Mode::Output
+            | Mode::Renaming
+            | Mode::Training) => {
+                log::error!(
+                    "The {mode} is not supported for ZIP archives, yet."
+                );
+                Err(ExitCode::Usage)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Kind {
+    Ar,
+    Brotli,
+    Cpio,
+    Tar,
+    #[cfg(feature = "zip")]
+    Zip,
+}
+
+impl Kind {
+    fn detect(path: &Path) -> Result<Self> {
+        let path = &crate::volume::base(path);
+        let inner = path.file_stem().map(Path::new).and_then(Path::extension);
+        let tarball = inner.is_some_and(|e| e == "tar");
+
+        match path.extension().and_then(std::ffi::OsStr::to_str) {
+            Some("a" | "ar" | "deb") => Ok(Self::Ar),
+            Some("br") if !tarball => Ok(Self::Brotli),
+            Some("br" | "tar" | "tbr") => Ok(Self::Tar),
+            #[cfg(feature = "bzip2")]
+            Some("bz2") if tarball => Ok(Self::Tar),
+            #[cfg(feature = "bzip2")]
+            Some("tb2" | "tbz" | "tbz2") => Ok(Self::Tar),
+            #[cfg(feature = "gzip")]
+            Some("gz") if tarball => Ok(Self::Tar),
+            #[cfg(feature = "gzip")]
+            Some("tgz") => Ok(Self::Tar),
+            #[cfg(feature = "xz")]
+            Some("txz") => Ok(Self::Tar),
+            #[cfg(feature = "xz")]
+            Some("xz") if tarball => Ok(Self::Tar),
+            #[cfg(feature = "zstd")]
+            Some("tzst") => Ok(Self::Tar),
+            #[cfg(feature = "zstd")]
+            Some("zst") if tarball => Ok(Self::Tar),
+            #[cfg(feature = "zip")]
+            Some("zip") => Ok(Self::Zip),
+            Some("cpio") => Ok(Self::Cpio),
+            Some(_) if inner.is_some_and(|e| e == "cpio") => Ok(Self::Cpio),
+            _ if crate::Compression::sniff(path).is_some() => Ok(Self::Tar),
+            None => {
+                log::error!(
+                    "Please specify the archive to work on with its extension."
+                );
+                Err(ExitCode::Usage)
+            }
+            Some(_) => {
+                log::error!("This archive type is not supported.");
+                Err(ExitCode::Usage)
+            }
         }
     }
 }
@@ -125,16 +1289,56 @@ pub enum Mode {
     /// Show this archive's contents.
     Content,
 
+    /// Convert this archive into another format.
+    Conversion,
+
+    /// Compare this archive with another archive or a directory.
+    Difference,
+
     /// Extract this archive's contents.
     Extraction,
 
+    /// Merge other archives into this archive.
+    Merge,
+
+    /// Print the content of a single member of this archive.
+    Output,
+
     /// Remove this archive from the file system.
     Removal,
 
+    /// Rename members of this archive.
+    Renaming,
+
+    /// Test this archive's integrity.
+    Test,
+
+    /// Train a Brotli dictionary on the given sample files.
+    Training,
+
     /// Update this archive.
     Update,
 }
 
+impl Mode {
+    fn of(command: &str) -> Option<Self> {
+        match command {
+            "cat" => Some(Self::Output),
+            "convert" => Some(Self::Conversion),
+            "create" => Some(Self::Update),
+            "delete" => Some(Self::Removal),
+            "diff" => Some(Self::Difference),
+            "extract" => Some(Self::Extraction),
+            "list" => Some(Self::Content),
+            "merge" => Some(Self::Merge),
+            "rename" => Some(Self::Renaming),
+            "test" => Some(Self::Test),
+            "train" => Some(Self::Training),
+            _ => None,
+        }
+    }
+}
+
 impl std::fmt::Display for Mode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -142,8 +1346,15 @@ impl std::fmt::Display for Mode {
             "{}",
             match self {
                 Self::Content => "archive content preview",
+                Self::Conversion => "archive conversion",
+                Self::Difference => "archive comparison",
                 Self::Extraction => "archive extraction",
+                Self::Merge => "archive merge",
+                Self::Output => "archive member output",
                 Self::Removal => "archive removal",
+                Self::Renaming => "archive member renaming",
+                Self::Test => "archive integrity test",
+                Self::Training => "dictionary training",
                 Self::Update => "archive update",
             }
         )
@@ -154,15 +1365,84 @@ impl std::str::FromStr for Mode {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        match s {
-            "compress" | "create" | "edit" | "update" => Ok(Self::Update),
-            "content" | "info" | "list" | "show" => Ok(Self::Content),
-            "delete" | "remove" => Ok(Self::Removal),
-            "decompress" | "extract" | "uncompress" | "unpack" => {
-                Ok(Self::Extraction)
-            }
-            _ => Err(format!("'{s}' is not supported, yet")),
-        }
+        Application::command()
+            .get_subcommands()
+            .find(|c| {
+                c.get_name() == s || c.get_visible_aliases().any(|a| a == s)
+            })
+            .and_then(|c| Self::of(c.get_name()))
+            .ok_or_else(|| format!("'{s}' is not supported, yet"))
+    }
+}
+
+fn ask(path: &Path) -> std::io::Result<bool> {
+    let mut answer = String::new();
+
+    eprint!("{}:  replace? [y/N] ", path.display());
+    std::io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+#[cfg(unix)]
+fn process_umask() -> u32 {
+    use rustix::{fs::Mode, process::umask};
+
+    let mask = umask(Mode::empty());
+    umask(mask);
+
+    // The mode type is narrower than `u32` on some platforms.
+    #[allow(clippy::useless_conversion)]
+    u32::from(mask.bits())
+}
+
+#[cfg(not(unix))]
+const fn process_umask() -> u32 {
+    0
+}
+
+#[cfg(unix)]
+fn superuser() -> bool {
+    rustix::process::geteuid().is_root()
+}
+
+#[cfg(not(unix))]
+const fn superuser() -> bool {
+    false
+}
+
+fn umask(s: &str) -> std::result::Result<u32, String> {
+    match u32::from_str_radix(s, 8) {
+        Ok(mask) if mask <= 0o7777 => Ok(mask),
+        _ => Err(format!("'{s}' is not a valid umask")),
+    }
+}
+
+fn volume_size(s: &str) -> std::result::Result<u64, String> {
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let factor: u64 = match s[digits..].to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KIB" => 1 << 10,
+        "M" | "MIB" => 1 << 20,
+        "G" | "GIB" => 1 << 30,
+        "T" | "TIB" => 1 << 40,
+        "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        unit => return Err(format!("'{unit}' is not a supported unit")),
+    };
+
+    match s[..digits]
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(factor))
+    {
+        Some(0) | None => Err(format!("'{s}' is not a valid volume size")),
+        Some(size) => Ok(size),
     }
 }
 
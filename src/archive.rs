@@ -17,13 +17,261 @@
 |                                                                              |
 \******************************************************************************/
 
+use crate::{
+    compression::{Encoder, Tuning},
+    diff::Member,
+    extract,
+    lock::Lock,
+    seekable, volume, Action, Compression, Dictionary, Digest, ExtractOptions,
+    Locking, Manifest, Plan,
+};
 use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
     ffi::OsStr,
     fs::{remove_file, File},
+    io::{self, Read},
     path::{Path, PathBuf},
 };
 use sysexits::{ExitCode, Result};
-use tar::{Archive, Builder};
+use tar::{Archive, Builder, Header};
+
+/// The PAX record prefix of extended attributes, as used by GNU tar and star.
+const XATTR_PREFIX: &[u8] = b"SCHILY.xattr.";
+
+/// The abstraction of an ar archive.
+///
+/// This abstraction can be used to read ar archives, such as Debian packages
+/// and static libraries, in the file system.  It supports extraction, removal,
+/// content information, and the conversion into TAR archives.
+pub struct Ar {
+    options: ExtractOptions,
+    path: PathBuf,
+}
+
+impl Ar {
+    /// Convert this ar archive into the given TAR archive.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn convert(&self, target: &Tar) -> Result<()> {
+        let _lock = target.lock()?;
+        let mut builder = Builder::new(target.writer()?);
+        self.entries(|entry, data| entry.append(&mut builder, data))?;
+        builder.into_inner()?.finish()?.persist()?;
+
+        Ok(())
+    }
+
+    fn entries<F>(&self, mut visit: F) -> Result<()>
+    where
+        F: FnMut(&Entry, &mut dyn Read) -> Result<()>,
+    {
+        let mut reader = io::BufReader::new(File::open(&self.path)?);
+        let mut magic = [0; 8];
+        let mut names = Vec::new();
+
+        if read_block(&mut reader, &mut magic)? != 8 || &magic != b"!<arch>\n" {
+            return Err(malformed(&self.path, "not an ar archive"));
+        }
+
+        loop {
+            let mut header = [0; 60];
+
+            match read_block(&mut reader, &mut header)? {
+                0 => return Ok(()),
+                60 if &header[58..] == b"`\n" => {}
+                _ => return Err(malformed(&self.path, "truncated header")),
+            }
+
+            let field = |start: usize, end: usize| {
+                std::str::from_utf8(&header[start..end])
+                    .map(str::trim_end)
+                    .unwrap_or_default()
+            };
+            let number = |start: usize, end: usize, radix: u32| {
+                let field = field(start, end);
+
+                if field.is_empty() {
+                    Ok(0)
+                } else {
+                    u64::from_str_radix(field, radix).map_err(|_| {
+                        malformed(
+                            &self.path,
+                            &format!("invalid field '{field}'"),
+                        )
+                    })
+                }
+            };
+            let size = number(48, 58, 10)?;
+            let mut data = (&mut reader).take(size);
+            let name = match field(0, 16) {
+                "/" | "/SYM64/" | "__.SYMDEF" | "__.SYMDEF SORTED" => None,
+                "//" => {
+                    names.clear();
+                    data.read_to_end(&mut names)?;
+                    None
+                }
+                name => Some(if let Some(length) = name.strip_prefix("#1/") {
+                    let mut name = Vec::new();
+                    (&mut data)
+                        .take(length.parse().map_err(|_| {
+                            malformed(&self.path, "invalid name length")
+                        })?)
+                        .read_to_end(&mut name)?;
+                    String::from_utf8_lossy(&name)
+                        .trim_end_matches('\0')
+                        .to_string()
+                } else if let Some(offset) =
+                    name.strip_prefix('/').and_then(|o| o.parse::<usize>().ok())
+                {
+                    let name = names.get(offset..).unwrap_or_default();
+                    String::from_utf8_lossy(
+                        name.split(|b| *b == b'\n').next().unwrap_or_default(),
+                    )
+                    .trim_end_matches('/')
+                    .to_string()
+                } else {
+                    name.trim_end_matches('/').to_string()
+                }),
+            };
+
+            if let Some(name) = name {
+                let mode =
+                    u32::try_from(number(40, 48, 8)?).unwrap_or_default();
+
+                visit(
+                    &Entry {
+                        device: (0, 0),
+                        kind: Entry::kind(mode),
+                        mode: mode & 0o7777,
+                        mtime: number(16, 28, 10)?,
+                        owner: Some((
+                            u32::try_from(number(28, 34, 10)?)
+                                .unwrap_or_default(),
+                            u32::try_from(number(34, 40, 10)?)
+                                .unwrap_or_default(),
+                        )),
+                        path: Entry::sanitise(&self.path, &name)?,
+                        size: data.limit(),
+                    },
+                    &mut data,
+                )?;
+            }
+
+            io::copy(&mut data, &mut io::sink())?;
+
+            if size % 2 == 1 {
+                read_block(&mut reader, &mut [0])?;
+            }
+        }
+    }
+
+    /// Whether this ar archive already exists in the file system.
+    #[must_use]
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Extract this ar archive's files.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn extract<P>(&self, destination: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        unpack_entries(
+            &self.path,
+            |visit| self.entries(visit),
+            destination.as_ref(),
+            None,
+            &self.options,
+        )
+    }
+
+    /// Extract the given members of this ar archive.
+    ///
+    /// Each member which cannot be found will be reported.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn extract_members<P, Q>(
+        &self,
+        destination: P,
+        members: &[Q],
+    ) -> Result<()>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        unpack_entries(
+            &self.path,
+            |visit| self.entries(visit),
+            destination.as_ref(),
+            Some(
+                &members
+                    .iter()
+                    .map(|m| m.as_ref().to_path_buf())
+                    .collect::<Vec<_>>(),
+            ),
+            &self.options,
+        )
+    }
+
+    /// List the content of this ar archive.
+    ///
+    /// This method will return a list of [`std::path::PathBuf`]s of all members
+    /// of this ar archive, excluding any symbol and name tables.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn list(&self) -> Result<Vec<PathBuf>> {
+        let mut result = Vec::new();
+
+        self.entries(|entry, _| {
+            result.push(entry.path.clone());
+            Ok(())
+        })?;
+
+        Ok(result)
+    }
+
+    /// Create a new instance.  This method **does not** create a new ar
+    /// archive in the file system.
+    pub fn new<P>(path: P) -> Self
+    where
+        PathBuf: From<P>,
+    {
+        Self {
+            options: ExtractOptions::default(),
+            path: path.into(),
+        }
+    }
+
+    /// Remove this ar archive from the file system.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn remove(&self) -> Result<()> {
+        Ok(remove_file(&self.path)?)
+    }
+
+    /// Set the metadata to restore when extracting this ar archive.
+    #[must_use]
+    pub const fn with_extract_options(
+        mut self,
+        options: ExtractOptions,
+    ) -> Self {
+        self.options = options;
+        self
+    }
+}
 
 /// The abstraction of a Brotli archive.
 ///
@@ -31,7 +279,11 @@ use tar::{Archive, Builder};
 /// system.  It supports transactions such as creation, updating, extraction,
 /// removal, and content information.
 pub struct Brotli {
+    dictionary: Option<Dictionary>,
+    locking: Locking,
+    options: ExtractOptions,
     path: PathBuf,
+    volume_size: Option<u64>,
 }
 
 impl Brotli {
@@ -43,6 +295,8 @@ impl Brotli {
     ///
     /// - quality:  11 (best possible compression rate).
     ///
+    /// In case that a custom dictionary was set, it will be used, as well.
+    ///
     /// # Errors
     ///
     /// See [`sysexits::ExitCode`].
@@ -50,15 +304,18 @@ impl Brotli {
     where
         P: AsRef<Path>,
     {
-        brotli::BrotliCompress(
-            &mut File::open(path)?,
-            &mut File::create(&self.path)?,
-            &brotli::enc::BrotliEncoderParams {
-                quality: 11,
-                ..Default::default()
+        let _lock = self.lock()?;
+        let mut encoder = Compression::Brotli.writer(
+            volume::create(&self.path, self.volume_size)?,
+            &Tuning {
+                dictionary: self.dictionary.clone(),
+                ..Tuning::default()
             },
         )?;
 
+        io::copy(&mut File::open(path)?, &mut encoder)?;
+        encoder.finish()?.persist()?;
+
         Ok(())
     }
 
@@ -71,154 +328,448 @@ impl Brotli {
     where
         P: AsRef<Path>,
     {
-        let source = self
-            .path
-            .file_name()
-            .ok_or(ExitCode::DataErr)?
-            .to_str()
-            .ok_or(ExitCode::DataErr)?;
-        let target = destination
-            .as_ref()
-            .to_str()
-            .ok_or(ExitCode::DataErr)?
-            .to_string()
-            + "/"
-            + source.strip_suffix(".br").map_or(source, |s| s);
+        let (target, mtime) = self.target(destination.as_ref())?;
 
-        Ok(brotli::BrotliDecompress(
-            &mut File::open(&self.path)?,
-            &mut File::create(target)?,
-        )?)
+        if self.options.prepare(&target, mtime)? {
+            io::copy(&mut self.reader()?, &mut File::create(target)?)?;
+        }
+
+        Ok(())
     }
 
-    /// Whether this Brotli archive already exists in the file system.
+    /// Whether this Brotli archive already exists in the file system, either
+    /// as a single file or as a set of volumes.
     #[must_use]
     pub fn exists(&self) -> bool {
-        self.path.exists()
+        volume::exists(&self.path)
+    }
+
+    fn lock(&self) -> Result<Lock> {
+        Lock::acquire(&self.path, self.locking)
     }
 
     /// Create a new instance.  This method **does not** create a new Brotli
     /// archive in the file system.
+    ///
+    /// The path may also name any volume of a multi-volume archive, such as
+    /// `archive.br.001`.
     pub fn new<P>(path: P) -> Self
     where
         PathBuf: From<P>,
     {
-        Self { path: path.into() }
+        Self {
+            dictionary: None,
+            locking: Locking::default(),
+            options: ExtractOptions::default(),
+            path: volume::base(&PathBuf::from(path)),
+            volume_size: None,
+        }
+    }
+
+    /// The changes compressing the given file would apply, without applying
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn plan_compress<P>(&self, path: P) -> Result<Plan>
+    where
+        P: AsRef<Path>,
+    {
+        File::open(path)?;
+        Ok(Plan::write(&self.path))
+    }
+
+    /// The changes decompressing this Brotli archive into the given directory
+    /// would apply, without applying them.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn plan_decompress<P>(&self, destination: P) -> Result<Plan>
+    where
+        P: AsRef<Path>,
+    {
+        if !self.exists() {
+            return Err(io::Error::from(io::ErrorKind::NotFound).into());
+        }
+
+        let (target, mtime) = self.target(destination.as_ref())?;
+        let mut plan = Plan::default();
+
+        plan.push(self.options.plan(&target, mtime)?);
+        Ok(plan)
+    }
+
+    /// The changes removing this Brotli archive would apply, without applying
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn plan_remove(&self) -> Result<Plan> {
+        Ok(Plan::remove(&self.path)?)
+    }
+
+    fn reader(&self) -> Result<Box<dyn Read>> {
+        Ok(Compression::Brotli
+            .reader(volume::open(&self.path)?, self.dictionary.as_ref())?)
     }
 
-    /// Remove this Brotli archive from the file system.
+    /// Remove this Brotli archive from the file system, including all of its
+    /// volumes.
     ///
     /// # Errors
     ///
     /// See [`sysexits::ExitCode`].
     pub fn remove(&self) -> Result<()> {
-        Ok(remove_file(&self.path)?)
+        let _lock = self.exists().then(|| self.lock()).transpose()?;
+        Ok(volume::remove(&self.path)?)
+    }
+
+    fn target(&self, destination: &Path) -> Result<(PathBuf, u64)> {
+        let source = self
+            .path
+            .file_name()
+            .ok_or(ExitCode::DataErr)?
+            .to_str()
+            .ok_or(ExitCode::DataErr)?;
+        let mtime = volume::paths(&self.path)
+            .first()
+            .map(std::fs::metadata)
+            .transpose()?
+            .map(|m| m.modified())
+            .transpose()?
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+
+        Ok((
+            destination.join(source.strip_suffix(".br").map_or(source, |s| s)),
+            mtime,
+        ))
+    }
+
+    /// Test this Brotli archive's integrity.
+    ///
+    /// The Brotli stream will be decoded entirely.  In case that this archive
+    /// is a TAR.BR archive, each TAR header of the decoded stream will be
+    /// validated, as well.  The first corrupt entry will be reported together
+    /// with its offset in the decoded stream.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn test(&self) -> Result<()> {
+        let mut decoder = self.reader()?;
+        let tarball = self.path.extension().is_some_and(|e| e == "tbr")
+            || self
+                .path
+                .file_stem()
+                .map(Path::new)
+                .and_then(Path::extension)
+                .is_some_and(|e| e == "tar");
+
+        if tarball {
+            verify(&self.path, decoder)
+        } else {
+            io::copy(&mut decoder, &mut io::sink()).map_or_else(
+                |e| {
+                    log::error!("{}:  {e}", self.path.display());
+                    Err(ExitCode::DataErr)
+                },
+                |_| Ok(()),
+            )
+        }
+    }
+
+    /// Use the given custom dictionary for compression and decompression.
+    ///
+    /// Streams which do not name a dictionary will be decompressed without it.
+    #[must_use]
+    pub fn with_dictionary(mut self, dictionary: Dictionary) -> Self {
+        self.dictionary = Some(dictionary);
+        self
+    }
+
+    /// Set how to treat an existing file when decompressing this Brotli
+    /// archive.
+    #[must_use]
+    pub const fn with_extract_options(
+        mut self,
+        options: ExtractOptions,
+    ) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set how to wait for this Brotli archive if another process is
+    /// modifying it.
+    #[must_use]
+    pub const fn with_locking(mut self, locking: Locking) -> Self {
+        self.locking = locking;
+        self
+    }
+
+    /// Split this Brotli archive into volumes of the given size in bytes when
+    /// writing it.
+    ///
+    /// The volumes will be numbered by an additional extension, starting with
+    /// `.001`.
+    #[must_use]
+    pub const fn with_volume_size(mut self, size: u64) -> Self {
+        self.volume_size = Some(size);
+        self
     }
 }
 
-/// The abstraction of a TAR archive.
+/// The ways to resolve duplicate member paths when merging archives.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Conflict {
+    /// Refuse to merge archives sharing any member path.
+    #[default]
+    Error,
+
+    /// Keep the first occurrence of each member path.
+    First,
+
+    /// Keep the last occurrence of each member path.
+    Last,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Error => "error",
+                Self::First => "first wins",
+                Self::Last => "last wins",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for Conflict {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "error" | "fail" => Ok(Self::Error),
+            "first" | "first-wins" | "keep" => Ok(Self::First),
+            "last" | "last-wins" | "replace" => Ok(Self::Last),
+            _ => Err(format!("'{s}' is not supported, yet")),
+        }
+    }
+}
+
+/// The abstraction of a cpio archive.
 ///
-/// This abstraction can be used to interact with TAR archives in the file
-/// system.  It supports transactions such as creation, updating, extraction,
-/// removal, and content information.
-pub struct Tar {
+/// This abstraction can be used to read cpio archives, such as initramfs
+/// images, in the file system.  Both the portable ASCII and the new ASCII
+/// formats are supported, optionally wrapped in a compression layer.  It
+/// supports extraction, removal, content information, and the conversion into
+/// TAR archives.
+pub struct Cpio {
+    options: ExtractOptions,
     path: PathBuf,
 }
 
-impl Tar {
-    /// Add a file to this TAR archive.
+impl Cpio {
+    /// Convert this cpio archive into the given TAR archive.
     ///
     /// # Errors
     ///
     /// See [`sysexits::ExitCode`].
-    pub fn add_files<P>(&self, paths: &[P]) -> Result<()>
+    pub fn convert(&self, target: &Tar) -> Result<()> {
+        let _lock = target.lock()?;
+        let mut builder = Builder::new(target.writer()?);
+        self.entries(|entry, data| entry.append(&mut builder, data))?;
+        builder.into_inner()?.finish()?.persist()?;
+
+        Ok(())
+    }
+
+    fn entries<F>(&self, mut visit: F) -> Result<()>
     where
-        P: AsRef<OsStr> + AsRef<Path>,
+        F: FnMut(&Entry, &mut dyn Read) -> Result<()>,
     {
-        let mut directories = Vec::new();
-        let mut files = Vec::new();
-        let mut symlinks = Vec::new();
+        let mut reader = Compression::detect(&self.path)
+            .reader(io::BufReader::new(File::open(&self.path)?), None)?;
 
-        for path in paths {
-            let path = PathBuf::from(path);
+        loop {
+            let mut magic = [0; 6];
 
-            if path.is_dir() {
-                directories.push(path.clone());
-                files.push(path);
-            } else if path.is_file() {
-                files.push(path);
-            } else if path.is_symlink() && path.read_link()?.exists() {
-                symlinks.push(path.read_link()?);
+            match read_block(&mut reader, &mut magic)? {
+                0 => return Ok(()),
+                6 => {}
+                _ => return Err(malformed(&self.path, "truncated header")),
             }
-        }
 
-        if self.exists() {
-            self.update(&files)
-        } else {
-            self.create(&files)
-        }?;
+            let (widths, radix, alignment): (&[usize], u32, u64) = match &magic
+            {
+                b"070701" | b"070702" => (&[8; 13], 16, 4),
+                b"070707" => (&[6, 6, 6, 6, 6, 6, 6, 11, 6, 11], 8, 1),
+                _ => {
+                    return Err(malformed(
+                        &self.path,
+                        "not a cpio archive in a supported format",
+                    ))
+                }
+            };
+            let mut header = vec![0; widths.iter().sum()];
 
-        for directory in directories {
-            let mut entries = Vec::new();
+            if read_block(&mut reader, &mut header)? != header.len() {
+                return Err(malformed(&self.path, "truncated header"));
+            }
+
+            let mut fields = Vec::with_capacity(widths.len());
+            let mut start = 0;
 
-            for entry in directory.read_dir()? {
-                entries.push(entry?.path());
+            for width in widths {
+                let field = std::str::from_utf8(&header[start..start + width])
+                    .ok()
+                    .and_then(|f| u64::from_str_radix(f, radix).ok())
+                    .ok_or_else(|| malformed(&self.path, "invalid header"))?;
+
+                fields.push(field);
+                start += width;
             }
 
-            if !entries.is_empty() {
-                self.add_files(&entries)?;
+            let (mode, owner, mtime, size, name_size, device) =
+                if alignment == 4 {
+                    (
+                        fields[1],
+                        (fields[2], fields[3]),
+                        fields[5],
+                        fields[6],
+                        fields[11],
+                        (fields[9], fields[10]),
+                    )
+                } else {
+                    (
+                        fields[2],
+                        (fields[3], fields[4]),
+                        fields[7],
+                        fields[9],
+                        fields[8],
+                        (fields[6] >> 8, fields[6] & 0xff),
+                    )
+                };
+            let mut name = Vec::new();
+
+            (&mut reader).take(name_size).read_to_end(&mut name)?;
+
+            if u64::try_from(name.len()).unwrap_or_default() != name_size {
+                return Err(malformed(&self.path, "truncated name"));
             }
-        }
 
-        if symlinks.is_empty() {
-            Ok(())
-        } else {
-            self.add_files(&symlinks)
-        }
-    }
+            skip_padding(&mut reader, 6 + start as u64 + name_size, alignment)?;
 
-    fn create<P>(&self, paths: &[P]) -> Result<()>
-    where
-        P: AsRef<Path>,
-    {
-        let mut archive = Builder::new(File::create(&self.path)?);
+            let name = String::from_utf8_lossy(&name)
+                .trim_end_matches('\0')
+                .to_string();
 
-        for path in paths {
-            archive.append_path(path).map_or_else(
-                |e| {
-                    archive.finish()?;
-                    eprintln!("{e}");
-                    Err(e)
-                },
-                Ok,
-            )?;
-        }
+            if name == "TRAILER!!!" {
+                return Ok(());
+            }
+
+            let mut data = (&mut reader).take(size);
+            let path = Entry::sanitise(&self.path, &name)?;
+
+            if !path.as_os_str().is_empty() {
+                let mode = u32::try_from(mode).unwrap_or_default();
+
+                visit(
+                    &Entry {
+                        device: (
+                            u32::try_from(device.0).unwrap_or_default(),
+                            u32::try_from(device.1).unwrap_or_default(),
+                        ),
+                        kind: Entry::kind(mode),
+                        mode: mode & 0o7777,
+                        mtime,
+                        owner: Some((
+                            u32::try_from(owner.0).unwrap_or_default(),
+                            u32::try_from(owner.1).unwrap_or_default(),
+                        )),
+                        path,
+                        size,
+                    },
+                    &mut data,
+                )?;
+            }
+
+            io::copy(&mut data, &mut io::sink())?;
 
-        Ok(archive.finish()?)
+            if data.limit() != 0 {
+                return Err(malformed(&self.path, "truncated data"));
+            }
+
+            skip_padding(&mut reader, size, alignment)?;
+        }
     }
 
-    /// Whether this TAR archive already exists in the file system.
+    /// Whether this cpio archive already exists in the file system.
     #[must_use]
     pub fn exists(&self) -> bool {
         self.path.exists()
     }
 
-    /// Extract this TAR archive's files.
+    /// Extract this cpio archive's files.
+    ///
+    /// Special files, such as device nodes, will be skipped.
     ///
     /// # Errors
     ///
     /// See [`sysexits::ExitCode`].
-    pub fn extract<P>(&self, destintation: P) -> Result<()>
+    pub fn extract<P>(&self, destination: P) -> Result<()>
     where
         P: AsRef<Path>,
     {
-        Ok(Archive::new(File::open(&self.path)?).unpack(destintation)?)
+        unpack_entries(
+            &self.path,
+            |visit| self.entries(visit),
+            destination.as_ref(),
+            None,
+            &self.options,
+        )
     }
 
-    /// List the content of this TAR archive.
+    /// Extract the given members of this cpio archive.
     ///
-    /// This method will return a list of [`std::path::PathBuf`]s of all entries
-    /// of this TAR archive.
+    /// Each member which cannot be found will be reported.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn extract_members<P, Q>(
+        &self,
+        destination: P,
+        members: &[Q],
+    ) -> Result<()>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        unpack_entries(
+            &self.path,
+            |visit| self.entries(visit),
+            destination.as_ref(),
+            Some(
+                &members
+                    .iter()
+                    .map(|m| m.as_ref().to_path_buf())
+                    .collect::<Vec<_>>(),
+            ),
+            &self.options,
+        )
+    }
+
+    /// List the content of this cpio archive.
+    ///
+    /// This method will return a list of [`std::path::PathBuf`]s of all members
+    /// of this cpio archive.
     ///
     /// # Errors
     ///
@@ -226,23 +777,27 @@ impl Tar {
     pub fn list(&self) -> Result<Vec<PathBuf>> {
         let mut result = Vec::new();
 
-        for entry in Archive::new(File::open(&self.path)?).entries()? {
-            result.push(PathBuf::from(entry?.path()?));
-        }
+        self.entries(|entry, _| {
+            result.push(entry.path.clone());
+            Ok(())
+        })?;
 
         Ok(result)
     }
 
-    /// Create a new instance.  This method **does not** create a new TAR
+    /// Create a new instance.  This method **does not** create a new cpio
     /// archive in the file system.
     pub fn new<P>(path: P) -> Self
     where
         PathBuf: From<P>,
     {
-        Self { path: path.into() }
+        Self {
+            options: ExtractOptions::default(),
+            path: path.into(),
+        }
     }
 
-    /// Remove this TAR archive from the file system.
+    /// Remove this cpio archive from the file system.
     ///
     /// # Errors
     ///
@@ -251,66 +806,1814 @@ impl Tar {
         Ok(remove_file(&self.path)?)
     }
 
-    fn update<P>(&self, paths: &[P]) -> Result<()>
+    /// Set the metadata to restore when extracting this cpio archive.
+    #[must_use]
+    pub const fn with_extract_options(
+        mut self,
+        options: ExtractOptions,
+    ) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// The abstraction of a TAR archive.
+///
+/// This abstraction can be used to interact with TAR archives in the file
+/// system.  It supports transactions such as creation, updating, extraction,
+/// removal, and content information.
+pub struct Tar {
+    compression: Compression,
+    locking: Locking,
+    options: ExtractOptions,
+    path: PathBuf,
+    tuning: Tuning,
+    volume_size: Option<u64>,
+    xattrs: bool,
+}
+
+impl Tar {
+    /// Add a file to this TAR archive.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn add_files<P>(&self, paths: &[P]) -> Result<()>
     where
         P: AsRef<OsStr> + AsRef<Path>,
     {
-        let directory =
-            tempfile::tempdir().map_or(Err(ExitCode::Unavailable), Ok)?;
-        let directory = directory.path().to_str().ok_or(ExitCode::DataErr)?;
-        let mut files = Vec::new();
-        let new_path = format!(
-            "{directory}/{}",
-            self.path
-                .file_name()
-                .ok_or(ExitCode::DataErr)?
-                .to_str()
-                .ok_or(ExitCode::DataErr)?
-        );
+        let _lock = self.lock()?;
+        add_recursively(paths, &|files| {
+            if self.exists() {
+                self.update(files)
+            } else {
+                self.create(files)
+            }
+        })
+    }
 
-        for path in paths {
-            files.push(PathBuf::from(path));
+    fn append<W>(&self, builder: &mut Builder<W>, path: &Path) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        log::debug!("{}:  adding {}", self.path.display(), path.display());
+
+        if self.xattrs {
+            append_pax(builder, &xattrs(path)?)?;
         }
 
-        let files = &files;
-        let mut new_archive = Builder::new(File::create(&new_path)?);
-        let mut old_archive = Archive::new(File::open(&self.path)?);
+        builder.append_path(path)
+    }
 
-        for file in files {
-            new_archive.append_path(file).map_or_else(
+    /// Write the content of the given member of this TAR archive.
+    ///
+    /// Seekable archives will only decode the segment of the given member.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn cat<P, W>(&self, member: P, output: &mut W) -> Result<()>
+    where
+        P: AsRef<Path>,
+        W: io::Write,
+    {
+        self.find(&[member], |entry| {
+            io::copy(entry, output)?;
+            Ok(())
+        })
+    }
+
+    /// The compression layer of this TAR archive.
+    #[must_use]
+    pub const fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Convert this TAR archive into the given one.
+    ///
+    /// The members will be streamed from this archive's compression layer into
+    /// the one of the target without materialising any intermediate files.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn convert(&self, target: &Self) -> Result<()> {
+        if self.path == target.path {
+            log::error!("Source and target of a conversion need to differ.");
+            return Err(ExitCode::Usage);
+        }
+
+        let _lock = target.lock()?;
+        let mut encoder = target.writer()?;
+
+        io::copy(&mut self.reader()?, &mut encoder)?;
+        encoder.finish()?.persist()?;
+
+        Ok(())
+    }
+
+    fn create<P>(&self, paths: &[P]) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut archive = Builder::new(self.writer()?);
+
+        for path in paths {
+            self.append(&mut archive, path.as_ref()).map_or_else(
                 |e| {
-                    new_archive.finish()?;
-                    eprintln!("{e}");
+                    archive.finish()?;
+                    log::error!("{e}");
                     Err(e)
                 },
                 Ok,
             )?;
         }
 
-        for entry in old_archive.entries()? {
+        archive.into_inner()?.finish()?.persist()?;
+
+        Ok(())
+    }
+
+    /// Whether this TAR archive already exists in the file system, either as
+    /// a single file or as a set of volumes.
+    #[must_use]
+    pub fn exists(&self) -> bool {
+        volume::exists(&self.path)
+    }
+
+    /// Extract this TAR archive's files.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn extract<P>(&self, destination: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let destination = destination.as_ref();
+        let mut archive = self.unpacker(self.reader()?);
+        let mut directories = Vec::new();
+
+        std::fs::create_dir_all(destination)?;
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+
+            if entry.header().entry_type().is_dir() {
+                directories.push(entry);
+            } else {
+                self.unpack(&mut entry, destination)?;
+            }
+        }
+
+        directories.sort_by(|a, b| b.path_bytes().cmp(&a.path_bytes()));
+
+        for mut directory in directories {
+            self.unpack(&mut directory, destination)?;
+        }
+
+        Ok(())
+    }
+
+    /// Extract the given members of this TAR archive.
+    ///
+    /// Seekable archives will only decode the segments of the given members.
+    /// Each member which cannot be found will be reported.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn extract_members<P, Q>(
+        &self,
+        destination: P,
+        members: &[Q],
+    ) -> Result<()>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        self.find(members, |entry| self.unpack(entry, destination.as_ref()))
+    }
+
+    fn find<P, F>(&self, members: &[P], mut visit: F) -> Result<()>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&mut tar::Entry<'_, Box<dyn Read>>) -> io::Result<()>,
+    {
+        let mut missing = members
+            .iter()
+            .map(|m| m.as_ref().to_path_buf())
+            .collect::<Vec<_>>();
+
+        if let Some(index) = self.index()? {
+            for member in members {
+                let Some(entry) =
+                    index.iter().find(|e| e.path == member.as_ref())
+                else {
+                    continue;
+                };
+                let reader: Box<dyn Read> = Box::new(seekable::segment(
+                    File::open(&self.path)?,
+                    entry.offset,
+                )?);
+
+                if let Some(entry) = self.unpacker(reader).entries()?.next() {
+                    visit(&mut entry?)?;
+                    missing.retain(|m| m != member.as_ref());
+                }
+            }
+        } else {
+            for entry in self.unpacker(self.reader()?).entries()? {
+                let mut entry = entry?;
+                let path = entry.path()?.into_owned();
+
+                if missing.contains(&path) {
+                    visit(&mut entry)?;
+                    missing.retain(|m| *m != path);
+                }
+            }
+        }
+
+        not_found(&self.path, &missing)
+    }
+
+    fn index(&self) -> Result<Option<Vec<seekable::Entry>>> {
+        if self.compression == Compression::Brotli && self.path.is_file() {
+            Ok(seekable::index(&mut File::open(&self.path)?)?)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// List the content of this TAR archive.
+    ///
+    /// This method will return a list of [`std::path::PathBuf`]s of all entries
+    /// of this TAR archive.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn list(&self) -> Result<Vec<PathBuf>> {
+        let mut result = Vec::new();
+
+        for entry in Archive::new(self.reader()?).entries()? {
+            result.push(PathBuf::from(entry?.path()?));
+        }
+
+        Ok(result)
+    }
+
+    fn lock(&self) -> Result<Lock> {
+        Lock::acquire(&self.path, self.locking)
+    }
+
+    /// Compute the checksum manifest of this TAR archive.
+    ///
+    /// The manifest will list the digests of all regular files stored in this
+    /// TAR archive.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn manifest(&self, digest: Digest) -> Result<Manifest> {
+        let mut result = Manifest::new(digest);
+
+        for entry in Archive::new(self.reader()?).entries()? {
+            let entry = entry?;
+
+            if entry.header().entry_type().is_file() {
+                let path = PathBuf::from(entry.path()?);
+                result.push(path, digest.hash(entry)?);
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub(crate) fn members(&self) -> Result<Vec<Member>> {
+        let mut result = Vec::new();
+
+        for entry in Archive::new(self.reader()?).entries()? {
             let entry = entry?;
-            let path = entry.path()?.into_owned();
-
-            if !files.contains(&path) {
-                let mut header = entry.header().clone();
-
-                new_archive
-                    .append_data(&mut header, path, entry)
-                    .map_or_else(
-                        |e| {
-                            new_archive.finish()?;
-                            eprintln!("{e}");
-                            Err(e)
-                        },
-                        Ok,
-                    )?;
+            let header = entry.header();
+            let is_file = header.entry_type().is_file();
+
+            result.push(Member {
+                path: PathBuf::from(entry.path()?),
+                size: if is_file { entry.size() } else { 0 },
+                mode: header.mode()? & 0o7777,
+                mtime: header.mtime()?,
+                hash: if is_file {
+                    Some(Digest::Blake3.hash(entry)?)
+                } else {
+                    None
+                },
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Merge the given TAR archives into this one.
+    ///
+    /// The members of this TAR archive, if it exists, will be followed by the
+    /// members of the given archives, in order.  Their compression layers may
+    /// differ.  Member paths occurring more than once will be resolved by the
+    /// given policy, treating this TAR archive as the first source.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn merge(&self, sources: &[Self], conflict: Conflict) -> Result<()> {
+        let _lock = self.lock()?;
+        let sources = self.merge_sources(sources)?;
+        let members = Self::merge_members(&sources, conflict)?;
+        let mut archive = Builder::new(self.writer()?);
+
+        for (tar, members) in sources.iter().zip(&members) {
+            let mut keep = members.iter().map(|(_, keep)| *keep);
+
+            copy_entries(
+                tar.reader()?,
+                &mut archive,
+                |_| keep.next().unwrap_or_default(),
+                Path::to_path_buf,
+            )?;
+        }
+
+        archive.into_inner()?.finish()?.persist()?;
+
+        Ok(())
+    }
+
+    fn merge_members(
+        sources: &[&Self],
+        conflict: Conflict,
+    ) -> Result<Vec<Vec<(PathBuf, bool)>>> {
+        let mut members = Vec::new();
+        let mut occurrences = HashMap::<PathBuf, Vec<(usize, usize)>>::new();
+
+        for (source, archive) in sources.iter().enumerate() {
+            let mut paths = Vec::new();
+
+            for (index, entry) in
+                Archive::new(archive.reader()?).entries()?.enumerate()
+            {
+                let path = entry?.path()?.into_owned();
+
+                occurrences
+                    .entry(path.clone())
+                    .or_default()
+                    .push((source, index));
+                paths.push(path);
+            }
+
+            members.push(paths);
+        }
+
+        let mut duplicates = occurrences
+            .iter()
+            .filter(|(_, o)| o.len() > 1)
+            .map(|(p, _)| p)
+            .collect::<Vec<_>>();
+
+        if conflict == Conflict::Error && !duplicates.is_empty() {
+            duplicates.sort();
+
+            for path in duplicates {
+                log::error!("{}:  duplicate member", path.display());
+            }
+
+            return Err(ExitCode::DataErr);
+        }
+
+        let keep = occurrences
+            .values()
+            .filter_map(|o| match conflict {
+                Conflict::Error | Conflict::First => o.first(),
+                Conflict::Last => o.last(),
+            })
+            .collect::<HashSet<_>>();
+
+        Ok(members
+            .into_iter()
+            .enumerate()
+            .map(|(source, paths)| {
+                paths
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, p)| (p, keep.contains(&(source, index))))
+                    .collect()
+            })
+            .collect())
+    }
+
+    fn merge_sources<'a>(
+        &'a self,
+        sources: &'a [Self],
+    ) -> Result<Vec<&'a Self>> {
+        if sources.iter().any(|s| s.path == self.path) {
+            log::error!("The merge target must not be one of its sources.");
+            return Err(ExitCode::Usage);
+        }
+
+        Ok(self
+            .exists()
+            .then_some(self)
+            .into_iter()
+            .chain(sources)
+            .collect())
+    }
+
+    /// Create a new instance.  This method **does not** create a new TAR
+    /// archive in the file system.
+    ///
+    /// The compression layer will be detected by the path's extension (see
+    /// [`Compression::detect`]).  The path may also name any volume of a
+    /// multi-volume archive, such as `archive.tar.br.001`.
+    pub fn new<P>(path: P) -> Self
+    where
+        PathBuf: From<P>,
+    {
+        let path = volume::base(&PathBuf::from(path));
+
+        Self {
+            compression: Compression::detect(&path),
+            locking: Locking::default(),
+            options: ExtractOptions::default(),
+            path,
+            tuning: Tuning::default(),
+            volume_size: None,
+            xattrs: false,
+        }
+    }
+
+    /// The changes adding the given files to this TAR archive would apply,
+    /// without applying them.
+    ///
+    /// Each file will either be added or replace the member with the same
+    /// path.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn plan_add_files<P>(&self, paths: &[P]) -> Result<Plan>
+    where
+        P: AsRef<OsStr> + AsRef<Path>,
+    {
+        let members = if self.exists() {
+            self.list()?
+        } else {
+            Vec::new()
+        };
+        let plan = RefCell::new(Plan::default());
+
+        add_recursively(paths, &|files| {
+            for file in files {
+                plan.borrow_mut().push(if members.contains(file) {
+                    Action::Replace(file.clone())
+                } else {
+                    Action::Add(file.clone())
+                });
+            }
+
+            Ok(())
+        })?;
+
+        Ok(plan.into_inner())
+    }
+
+    fn plan_entry<R>(
+        &self,
+        plan: &mut Plan,
+        entry: &tar::Entry<'_, R>,
+        destination: &Path,
+    ) -> io::Result<()>
+    where
+        R: Read,
+    {
+        let member = entry.path()?;
+
+        plan.push(match extract::destination(destination, &member) {
+            None => Action::Skip(member.into_owned()),
+            Some(path) if entry.header().entry_type().is_dir() => {
+                Action::Extract(path)
             }
+            Some(path) => self.options.plan(&path, entry.header().mtime()?)?,
+        });
+
+        Ok(())
+    }
+
+    /// The changes extracting this TAR archive's files would apply, without
+    /// applying them.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn plan_extract<P>(&self, destination: P) -> Result<Plan>
+    where
+        P: AsRef<Path>,
+    {
+        let mut plan = Plan::default();
+
+        for entry in self.unpacker(self.reader()?).entries()? {
+            self.plan_entry(&mut plan, &entry?, destination.as_ref())?;
         }
 
-        new_archive.finish()?;
+        Ok(plan)
+    }
+
+    /// The changes extracting the given members of this TAR archive would
+    /// apply, without applying them.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn plan_extract_members<P, Q>(
+        &self,
+        destination: P,
+        members: &[Q],
+    ) -> Result<Plan>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let mut plan = Plan::default();
+
+        self.find(members, |entry| {
+            self.plan_entry(&mut plan, entry, destination.as_ref())
+        })?;
 
-        Ok(std::fs::rename(new_path, &self.path)?)
+        Ok(plan)
     }
+
+    /// The changes merging the given TAR archives into this TAR archive would
+    /// apply, without applying them.
+    ///
+    /// Each member to copy will be added, including the existing members of
+    /// this TAR archive, and each duplicate member which the conflict policy
+    /// drops will be skipped.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn plan_merge(
+        &self,
+        sources: &[Self],
+        conflict: Conflict,
+    ) -> Result<Plan> {
+        let mut plan = Plan::default();
+
+        for (path, keep) in
+            Self::merge_members(&self.merge_sources(sources)?, conflict)?
+                .into_iter()
+                .flatten()
+        {
+            plan.push(if keep {
+                Action::Add(path)
+            } else {
+                Action::Skip(path)
+            });
+        }
+
+        plan.append(self.plan_write());
+        Ok(plan)
+    }
+
+    /// The changes removing this TAR archive would apply, without applying
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn plan_remove(&self) -> Result<Plan> {
+        Ok(Plan::remove(&self.path)?)
+    }
+
+    /// The changes renaming the members of this TAR archive would apply,
+    /// without applying them.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn plan_rename<P, Q>(&self, mapping: &[(P, Q)]) -> Result<Plan>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let mut plan = Plan::default();
+
+        for (old, new) in self.renamed_members(mapping)? {
+            if old != new {
+                plan.push(Action::Rename(old, new));
+            }
+        }
+
+        plan.append(self.plan_write());
+        Ok(plan)
+    }
+
+    /// The changes rewriting this TAR archive would apply, without applying
+    /// them, such as when converting another archive into it.
+    #[must_use]
+    pub fn plan_write(&self) -> Plan {
+        Plan::write(&self.path)
+    }
+
+    fn reader(&self) -> Result<Box<dyn Read>> {
+        Ok(self.compression.reader(
+            volume::open(&self.path)?,
+            self.tuning.dictionary.as_ref(),
+        )?)
+    }
+
+    /// Remove this TAR archive from the file system, including all of its
+    /// volumes.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn remove(&self) -> Result<()> {
+        let _lock = self.exists().then(|| self.lock()).transpose()?;
+        Ok(volume::remove(&self.path)?)
+    }
+
+    /// Rename members of this TAR archive.
+    ///
+    /// Each mapping renames the member with the given old path as well as all
+    /// members below it, such that whole directories can be moved at once.  The
+    /// first matching mapping will be applied.  The content and the metadata
+    /// of all members will be preserved, including the targets of hard links.
+    /// This archive will be rewritten in a single pass.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn rename<P, Q>(&self, mapping: &[(P, Q)]) -> Result<()>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let _lock = self.lock()?;
+
+        self.renamed_members(mapping)?;
+
+        let mut new_archive = Builder::new(self.writer()?);
+
+        copy_entries(
+            self.reader()?,
+            &mut new_archive,
+            |_| true,
+            |path| {
+                renamed(path, mapping)
+                    .map_or_else(|| path.to_path_buf(), |(_, new)| new)
+            },
+        )?;
+        new_archive.into_inner()?.finish()?.persist()?;
+
+        Ok(())
+    }
+
+    fn renamed_members<P, Q>(
+        &self,
+        mapping: &[(P, Q)],
+    ) -> Result<Vec<(PathBuf, PathBuf)>>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let mut found = vec![false; mapping.len()];
+        let members = self
+            .list()?
+            .into_iter()
+            .map(|path| {
+                let new = renamed(&path, mapping).map_or_else(
+                    || path.clone(),
+                    |(index, new)| {
+                        found[index] = true;
+                        new
+                    },
+                );
+
+                (path, new)
+            })
+            .collect::<Vec<_>>();
+        let missing = mapping
+            .iter()
+            .zip(found)
+            .filter(|(_, found)| !found)
+            .map(|((old, _), _)| old.as_ref().to_path_buf())
+            .collect::<Vec<_>>();
+
+        not_found(&self.path, &missing)?;
+
+        let mut occurrences = HashMap::<&Path, usize>::new();
+
+        for (_, new) in &members {
+            *occurrences.entry(new).or_default() += 1;
+        }
+
+        let mut collisions = members
+            .iter()
+            .filter(|(old, new)| old != new && occurrences[new.as_path()] > 1)
+            .map(|(_, new)| new)
+            .collect::<Vec<_>>();
+
+        if collisions.is_empty() {
+            return Ok(members);
+        }
+
+        collisions.sort();
+        collisions.dedup();
+
+        for path in collisions {
+            log::error!(
+                "{}:  {}:  already exists",
+                self.path.display(),
+                path.display()
+            );
+        }
+
+        Err(ExitCode::DataErr)
+    }
+
+    /// Test this TAR archive's integrity.
+    ///
+    /// Each TAR header will be validated regarding its checksum and size.  The
+    /// first corrupt entry will be reported together with its offset.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn test(&self) -> Result<()> {
+        verify(&self.path, self.reader()?)
+    }
+
+    fn unpack<R>(
+        &self,
+        entry: &mut tar::Entry<'_, R>,
+        destination: &Path,
+    ) -> io::Result<()>
+    where
+        R: Read,
+    {
+        let Some(path) = extract::destination(destination, &entry.path()?)
+        else {
+            entry.unpack_in(destination)?;
+            return Ok(());
+        };
+
+        log::debug!("{}:  extracting {}", self.path.display(), path.display());
+        extract::confine(destination, &path)?;
+
+        if !entry.header().entry_type().is_dir()
+            && !self.options.prepare(&path, entry.header().mtime()?)?
+        {
+            return Ok(());
+        }
+
+        if !entry.unpack_in(destination)?
+            || !self.options.ownership()
+            || self.options.numeric_owner()
+        {
+            return Ok(());
+        }
+
+        let header = entry.header();
+
+        self.options.restore_owner(
+            &path,
+            (
+                u32::try_from(header.uid()?).unwrap_or_default(),
+                u32::try_from(header.gid()?).unwrap_or_default(),
+            ),
+            (
+                header.username().ok().flatten(),
+                header.groupname().ok().flatten(),
+            ),
+        )
+    }
+
+    fn unpacker<R>(&self, reader: R) -> Archive<R>
+    where
+        R: Read,
+    {
+        let mut archive = Archive::new(reader);
+
+        archive.set_mask(self.options.mask());
+        archive.set_preserve_mtime(self.options.mtime());
+        archive.set_preserve_ownerships(self.options.ownership());
+        archive.set_preserve_permissions(self.options.permissions());
+        archive.set_unpack_xattrs(self.xattrs);
+        archive
+    }
+
+    fn update<P>(&self, paths: &[P]) -> Result<()>
+    where
+        P: AsRef<OsStr> + AsRef<Path>,
+    {
+        let mut files = Vec::new();
+
+        for path in paths {
+            files.push(PathBuf::from(path));
+        }
+
+        let files = &files;
+        let mut new_archive = Builder::new(self.writer()?);
+
+        for file in files {
+            self.append(&mut new_archive, file).map_or_else(
+                |e| {
+                    new_archive.finish()?;
+                    log::error!("{e}");
+                    Err(e)
+                },
+                Ok,
+            )?;
+        }
+
+        copy_entries(
+            self.reader()?,
+            &mut new_archive,
+            |path| !files.iter().any(|f| f == path),
+            Path::to_path_buf,
+        )?;
+        new_archive.into_inner()?.finish()?.persist()?;
+
+        Ok(())
+    }
+
+    /// Use the given custom dictionary for Brotli compression and
+    /// decompression.
+    ///
+    /// Only Brotli compressed TAR archives will consider this setting.
+    #[must_use]
+    pub fn with_dictionary(mut self, dictionary: Dictionary) -> Self {
+        self.tuning.dictionary = Some(dictionary);
+        self
+    }
+
+    /// Set the metadata to restore when extracting this TAR archive.
+    #[must_use]
+    pub const fn with_extract_options(
+        mut self,
+        options: ExtractOptions,
+    ) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the compression level to use when writing this TAR archive.
+    ///
+    /// The level will be clamped to the range supported by the compression
+    /// layer.  Uncompressed TAR archives will ignore it.
+    #[must_use]
+    pub const fn with_level(mut self, level: i32) -> Self {
+        self.tuning.level = Some(level);
+        self
+    }
+
+    /// Set how to wait for this TAR archive if another process is
+    /// modifying it.
+    #[must_use]
+    pub const fn with_locking(mut self, locking: Locking) -> Self {
+        self.locking = locking;
+        self
+    }
+
+    /// Write this TAR archive in the seekable layout.
+    ///
+    /// Only Brotli compressed TAR archives will consider this setting.  Their
+    /// members will be compressed independently and indexed such that single
+    /// members can be accessed without decoding the entire archive.  Such
+    /// archives remain valid Brotli streams for any other decoder.
+    #[must_use]
+    pub const fn with_seekable(mut self) -> Self {
+        self.tuning.seekable = true;
+        self
+    }
+
+    /// Split this TAR archive into volumes of the given size in bytes when
+    /// writing it.
+    ///
+    /// The volumes will be numbered by an additional extension, starting with
+    /// `.001`.
+    #[must_use]
+    pub const fn with_volume_size(mut self, size: u64) -> Self {
+        self.volume_size = Some(size);
+        self
+    }
+
+    /// Enable long distance matching with the given window size, expressed as
+    /// a power of two, when writing this TAR archive.
+    ///
+    /// Only Zstandard compressed TAR archives will consider this setting.
+    #[must_use]
+    pub const fn with_window(mut self, window_log: u32) -> Self {
+        self.tuning.window = Some(window_log);
+        self
+    }
+
+    /// Capture and restore extended attributes.
+    ///
+    /// When writing this TAR archive, the extended attributes of each file will
+    /// be stored as PAX records in the format of GNU tar and star.  This
+    /// includes POSIX ACLs and file capabilities, which Linux exposes as the
+    /// `system.posix_acl_*` and `security.capability` attributes.  When
+    /// extracting this TAR archive, these records will be restored.
+    #[must_use]
+    pub const fn with_xattrs(mut self) -> Self {
+        self.xattrs = true;
+        self
+    }
+
+    fn writer(&self) -> Result<Encoder<volume::Writer>> {
+        Ok(self.compression.writer(
+            volume::create(&self.path, self.volume_size)?,
+            &self.tuning,
+        )?)
+    }
+}
+
+/// The abstraction of a ZIP archive.
+///
+/// This abstraction can be used to interact with ZIP archives in the file
+/// system.  It supports transactions such as creation, updating, extraction,
+/// removal, and content information.
+#[cfg(feature = "zip")]
+pub struct Zip {
+    level: Option<i32>,
+    locking: Locking,
+    options: ExtractOptions,
+    path: PathBuf,
+}
+
+#[cfg(feature = "zip")]
+impl Zip {
+    /// Add a file to this ZIP archive.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn add_files<P>(&self, paths: &[P]) -> Result<()>
+    where
+        P: AsRef<OsStr> + AsRef<Path>,
+    {
+        let _lock = self.lock()?;
+        add_recursively(paths, &|files| {
+            if self.exists() {
+                self.update(files)
+            } else {
+                self.create(files)
+            }
+        })
+    }
+
+    fn append<W>(
+        &self,
+        archive: &mut zip::ZipWriter<W>,
+        path: &Path,
+    ) -> Result<()>
+    where
+        W: io::Write + io::Seek,
+    {
+        log::debug!("{}:  adding {}", self.path.display(), path.display());
+
+        let metadata = path.metadata()?;
+        let name = zip_name(path)?;
+        let options = match self.level {
+            Some(0) => zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored),
+            level => zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated)
+                .compression_level(level.map(|l| i64::from(l.clamp(1, 9)))),
+        }
+        .last_modified_time(
+            zip::DateTime::try_from(
+                chrono::DateTime::<chrono::Local>::from(metadata.modified()?)
+                    .naive_local(),
+            )
+            .unwrap_or_else(|_| {
+                log::warn!(
+                    "{}:  modification time out of the ZIP range, using \
+                     1980-01-01",
+                    path.display()
+                );
+                zip::DateTime::default()
+            }),
+        )
+        .unix_permissions(crate::diff::mode(&metadata));
+
+        if metadata.is_dir() {
+            archive.add_directory(name, options).map_err(zip_error)
+        } else {
+            archive.start_file(name, options).map_err(zip_error)?;
+            io::copy(&mut File::open(path)?, archive)?;
+            Ok(())
+        }
+    }
+
+    fn archive(&self) -> Result<zip::ZipArchive<File>> {
+        zip::ZipArchive::new(File::open(&self.path)?).map_err(zip_error)
+    }
+
+    /// Convert this ZIP archive into the given TAR archive.
+    ///
+    /// The members will be streamed into the target's compression layer
+    /// without materialising any intermediate files.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn convert(&self, target: &Tar) -> Result<()> {
+        let _lock = target.lock()?;
+        let mut builder = Builder::new(target.writer()?);
+        self.entries(|entry, data| entry.append(&mut builder, data))?;
+        builder.into_inner()?.finish()?.persist()?;
+
+        Ok(())
+    }
+
+    fn create<P>(&self, paths: &[P]) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut archive = zip::ZipWriter::new(volume::temporary(&self.path)?);
+
+        for path in paths {
+            self.append(&mut archive, path.as_ref())?;
+        }
+
+        Ok(volume::persist(
+            archive.finish().map_err(zip_error)?,
+            &self.path,
+        )?)
+    }
+
+    fn entries<F>(&self, mut visit: F) -> Result<()>
+    where
+        F: FnMut(&Entry, &mut dyn Read) -> Result<()>,
+    {
+        let mut archive = self.archive()?;
+
+        for index in 0..archive.len() {
+            let mut data = archive.by_index(index).map_err(zip_error)?;
+            let path = data.enclosed_name().ok_or(ExitCode::DataErr)?;
+            let kind = if data.is_dir() {
+                tar::EntryType::Directory
+            } else if data.is_symlink() {
+                tar::EntryType::Symlink
+            } else {
+                tar::EntryType::Regular
+            };
+
+            visit(
+                &Entry {
+                    device: (0, 0),
+                    kind,
+                    mode: data.unix_mode().map_or_else(
+                        || if data.is_dir() { 0o755 } else { 0o644 },
+                        |m| m & 0o7777,
+                    ),
+                    mtime: data
+                        .last_modified()
+                        .and_then(|t| chrono::NaiveDateTime::try_from(t).ok())
+                        .and_then(|t| {
+                            t.and_local_timezone(chrono::Local).earliest()
+                        })
+                        .map_or(0, |t| {
+                            u64::try_from(t.timestamp()).unwrap_or_default()
+                        }),
+                    owner: None,
+                    path,
+                    size: data.size(),
+                },
+                &mut data,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether this ZIP archive already exists in the file system.
+    #[must_use]
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Extract this ZIP archive's files.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn extract<P>(&self, destination: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        unpack_entries(
+            &self.path,
+            |visit| self.entries(visit),
+            destination.as_ref(),
+            None,
+            &self.options,
+        )
+    }
+
+    /// Extract the given members of this ZIP archive.
+    ///
+    /// Each member which cannot be found will be reported.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn extract_members<P, Q>(
+        &self,
+        destination: P,
+        members: &[Q],
+    ) -> Result<()>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        unpack_entries(
+            &self.path,
+            |visit| self.entries(visit),
+            destination.as_ref(),
+            Some(
+                &members
+                    .iter()
+                    .map(|m| m.as_ref().to_path_buf())
+                    .collect::<Vec<_>>(),
+            ),
+            &self.options,
+        )
+    }
+
+    /// List the content of this ZIP archive.
+    ///
+    /// This method will return a list of [`std::path::PathBuf`]s of all entries
+    /// of this ZIP archive.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn list(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.archive()?.file_names().map(PathBuf::from).collect())
+    }
+
+    fn lock(&self) -> Result<Lock> {
+        Lock::acquire(&self.path, self.locking)
+    }
+
+    /// Create a new instance.  This method **does not** create a new ZIP
+    /// archive in the file system.
+    pub fn new<P>(path: P) -> Self
+    where
+        PathBuf: From<P>,
+    {
+        Self {
+            level: None,
+            locking: Locking::default(),
+            options: ExtractOptions::default(),
+            path: path.into(),
+        }
+    }
+
+    /// Remove this ZIP archive from the file system.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn remove(&self) -> Result<()> {
+        let _lock = self.exists().then(|| self.lock()).transpose()?;
+        Ok(remove_file(&self.path)?)
+    }
+
+    /// Test this ZIP archive's integrity.
+    ///
+    /// Each entry will be decompressed in order to validate its CRC-32.  The
+    /// first corrupt entry will be reported together with its offset.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn test(&self) -> Result<()> {
+        let mut archive = self.archive()?;
+
+        for index in 0..archive.len() {
+            let name = archive
+                .name_for_index(index)
+                .unwrap_or_default()
+                .to_string();
+            let report = |offset: u64, reason: String| {
+                log::error!(
+                    "{}:  entry {index} ('{name}') at offset {offset}:  {reason}",
+                    self.path.display()
+                );
+                Err(ExitCode::DataErr)
+            };
+
+            let offset = archive
+                .by_index_raw(index)
+                .map_err(zip_error)?
+                .header_start();
+            let result = archive
+                .by_index(index)
+                .map_err(|e| e.to_string())
+                .and_then(|mut entry| {
+                    io::copy(&mut entry, &mut io::sink())
+                        .map_err(|e| e.to_string())
+                });
+
+            if let Err(reason) = result {
+                return report(offset, reason);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update<P>(&self, paths: &[P]) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut new_archive =
+            zip::ZipWriter::new(volume::temporary(&self.path)?);
+        let mut old_archive = self.archive()?;
+        let mut names = Vec::new();
+
+        for path in paths {
+            self.append(&mut new_archive, path.as_ref())?;
+            names.push(PathBuf::from(zip_name(path.as_ref())?));
+        }
+
+        for index in 0..old_archive.len() {
+            let entry = old_archive.by_index_raw(index).map_err(zip_error)?;
+
+            if !names.contains(&PathBuf::from(entry.name())) {
+                new_archive.raw_copy_file(entry).map_err(zip_error)?;
+            }
+        }
+
+        Ok(volume::persist(
+            new_archive.finish().map_err(zip_error)?,
+            &self.path,
+        )?)
+    }
+
+    /// Set the metadata to restore when extracting this ZIP archive.
+    #[must_use]
+    pub const fn with_extract_options(
+        mut self,
+        options: ExtractOptions,
+    ) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the compression level to use when writing this ZIP archive.
+    ///
+    /// A level of zero will store the files without any compression.  Any
+    /// other level will be clamped to the range of the Deflate method.
+    #[must_use]
+    pub const fn with_level(mut self, level: i32) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Set how to wait for this ZIP archive if another process is
+    /// modifying it.
+    #[must_use]
+    pub const fn with_locking(mut self, locking: Locking) -> Self {
+        self.locking = locking;
+        self
+    }
+}
+
+struct Entry {
+    device: (u32, u32),
+    kind: tar::EntryType,
+    mode: u32,
+    mtime: u64,
+    owner: Option<(u32, u32)>,
+    path: PathBuf,
+    size: u64,
+}
+
+impl Entry {
+    fn append<W>(
+        &self,
+        builder: &mut Builder<W>,
+        data: &mut dyn Read,
+    ) -> Result<()>
+    where
+        W: io::Write,
+    {
+        let mut header = Header::new_gnu();
+
+        header.set_entry_type(self.kind);
+        header.set_mode(self.mode);
+        header.set_mtime(self.mtime);
+        header.set_size(0);
+
+        match self.kind {
+            tar::EntryType::Regular => {
+                header.set_size(self.size);
+                builder.append_data(&mut header, &self.path, data)?;
+            }
+            tar::EntryType::Symlink => {
+                let mut target = String::new();
+                data.read_to_string(&mut target)?;
+                builder.append_link(&mut header, &self.path, target)?;
+            }
+            _ => {
+                header.set_device_major(self.device.0)?;
+                header.set_device_minor(self.device.1)?;
+                builder.append_data(&mut header, &self.path, io::empty())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    const fn kind(mode: u32) -> tar::EntryType {
+        match mode & 0o170_000 {
+            0o010_000 => tar::EntryType::Fifo,
+            0o020_000 => tar::EntryType::Char,
+            0o040_000 => tar::EntryType::Directory,
+            0o060_000 => tar::EntryType::Block,
+            0o120_000 => tar::EntryType::Symlink,
+            _ => tar::EntryType::Regular,
+        }
+    }
+
+    fn sanitise(archive: &Path, name: &str) -> Result<PathBuf> {
+        let mut result = PathBuf::new();
+
+        for component in Path::new(name).components() {
+            match component {
+                std::path::Component::Normal(c) => result.push(c),
+                std::path::Component::ParentDir => {
+                    return Err(malformed(
+                        archive,
+                        &format!("member '{name}' escapes the destination"),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn unpack(
+        &self,
+        destination: &Path,
+        data: &mut dyn Read,
+        options: &ExtractOptions,
+    ) -> Result<()> {
+        let path = destination.join(&self.path);
+
+        log::debug!("extracting {}", path.display());
+        extract::confine(destination, &path)?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        match self.kind {
+            tar::EntryType::Directory => std::fs::create_dir_all(&path)?,
+            tar::EntryType::Regular => {
+                if !options.prepare(&path, self.mtime)? {
+                    return Ok(());
+                }
+
+                let mut file = File::create(&path)?;
+                io::copy(data, &mut file)?;
+
+                if options.mtime() {
+                    file.set_modified(
+                        std::time::UNIX_EPOCH
+                            + std::time::Duration::from_secs(self.mtime),
+                    )?;
+                }
+
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+
+                    file.set_permissions(std::fs::Permissions::from_mode(
+                        options.mode(self.mode),
+                    ))?;
+                }
+            }
+            #[cfg(unix)]
+            tar::EntryType::Symlink => {
+                let mut target = String::new();
+                data.read_to_string(&mut target)?;
+
+                if !options.prepare(&path, self.mtime)? {
+                    return Ok(());
+                }
+
+                std::os::unix::fs::symlink(target, &path)?;
+            }
+            _ => {
+                log::warn!(
+                    "{}:  special files are not supported, skipping",
+                    self.path.display()
+                );
+                return Ok(());
+            }
+        }
+
+        if let Some(owner) = self.owner {
+            options.restore_owner(&path, owner, (None, None))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn add_recursively<P, F>(paths: &[P], write: &F) -> Result<()>
+where
+    P: AsRef<OsStr> + AsRef<Path>,
+    F: Fn(&[PathBuf]) -> Result<()>,
+{
+    let mut directories = Vec::new();
+    let mut files = Vec::new();
+    let mut symlinks = Vec::new();
+
+    for path in paths {
+        let path = PathBuf::from(path);
+
+        if path.is_dir() {
+            directories.push(path.clone());
+            files.push(path);
+        } else if path.is_file() {
+            files.push(path);
+        } else if path.is_symlink() && path.read_link()?.exists() {
+            symlinks.push(path.read_link()?);
+        }
+    }
+
+    write(&files)?;
+
+    for directory in directories {
+        let mut entries = Vec::new();
+
+        for entry in directory.read_dir()? {
+            entries.push(entry?.path());
+        }
+
+        if !entries.is_empty() {
+            add_recursively(&entries, write)?;
+        }
+    }
+
+    if symlinks.is_empty() {
+        Ok(())
+    } else {
+        add_recursively(&symlinks, write)
+    }
+}
+
+fn append_pax<W>(builder: &mut Builder<W>, records: &[u8]) -> io::Result<()>
+where
+    W: io::Write,
+{
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let mut header = Header::new_ustar();
+
+    header.set_entry_type(tar::EntryType::XHeader);
+    header.set_path("././@PaxHeader")?;
+    header.set_mode(0o644);
+    header.set_size(records.len() as u64);
+    header.set_cksum();
+    builder.append(&header, records)
+}
+
+fn copy_entries<R, W, F, G>(
+    source: R,
+    target: &mut Builder<W>,
+    mut keep: F,
+    mut rename: G,
+) -> Result<()>
+where
+    R: Read,
+    W: io::Write,
+    F: FnMut(&Path) -> bool,
+    G: FnMut(&Path) -> PathBuf,
+{
+    for entry in Archive::new(source).entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+
+        if !keep(&path) {
+            log::debug!("dropping {}", path.display());
+            continue;
+        }
+
+        log::debug!("copying {}", path.display());
+
+        let mut records = Vec::new();
+
+        if let Some(extensions) = entry.pax_extensions()? {
+            for extension in extensions {
+                let extension = extension?;
+
+                if extension.key_bytes().starts_with(XATTR_PREFIX) {
+                    pax_record(
+                        &mut records,
+                        extension.key_bytes(),
+                        extension.value_bytes(),
+                    );
+                }
+            }
+        }
+
+        append_pax(target, &records)?;
+
+        let mut header = entry.header().clone();
+        let kind = header.entry_type();
+        let path = rename(&path);
+        let result = match entry.link_name()? {
+            Some(link) if kind.is_hard_link() => {
+                target.append_link(&mut header, path, rename(&link))
+            }
+            Some(link) if kind.is_symlink() => {
+                target.append_link(&mut header, path, link)
+            }
+            _ => target.append_data(&mut header, path, entry),
+        };
+
+        result.map_or_else(
+            |e| {
+                target.finish()?;
+                log::error!("{e}");
+                Err(e)
+            },
+            Ok,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn malformed(path: &Path, reason: &str) -> ExitCode {
+    log::error!("{}:  {reason}", path.display());
+    ExitCode::DataErr
+}
+
+fn not_found(archive: &Path, missing: &[PathBuf]) -> Result<()> {
+    for member in missing {
+        log::error!("{}:  {}:  not found", archive.display(), member.display());
+    }
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(ExitCode::DataErr)
+    }
+}
+
+/// Append a PAX record, which is prefixed by its own length in decimal digits.
+fn pax_record(records: &mut Vec<u8>, key: &[u8], value: &[u8]) {
+    let length = key.len() + value.len() + 3;
+    let mut total = length + 1;
+
+    while total != length + total.to_string().len() {
+        total = length + total.to_string().len();
+    }
+
+    records.extend_from_slice(total.to_string().as_bytes());
+    records.push(b' ');
+    records.extend_from_slice(key);
+    records.push(b'=');
+    records.extend_from_slice(value);
+    records.push(b'\n');
+}
+
+fn read_block<R>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize>
+where
+    R: Read + ?Sized,
+{
+    let mut length = 0;
+
+    while length < buffer.len() {
+        match reader.read(&mut buffer[length..]) {
+            Ok(0) => break,
+            Ok(n) => length += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(length)
+}
+
+fn renamed<P, Q>(path: &Path, mapping: &[(P, Q)]) -> Option<(usize, PathBuf)>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    mapping.iter().enumerate().find_map(|(index, (old, new))| {
+        let rest = path.strip_prefix(old).ok()?;
+
+        Some((
+            index,
+            if rest.as_os_str().is_empty() {
+                new.as_ref().to_path_buf()
+            } else {
+                new.as_ref().join(rest)
+            },
+        ))
+    })
+}
+
+fn skip_padding<R>(reader: &mut R, length: u64, alignment: u64) -> Result<()>
+where
+    R: Read + ?Sized,
+{
+    io::copy(
+        &mut reader.take((alignment - length % alignment) % alignment),
+        &mut io::sink(),
+    )?;
+
+    Ok(())
+}
+
+fn unpack_entries<E>(
+    archive: &Path,
+    entries: E,
+    destination: &Path,
+    members: Option<&[PathBuf]>,
+    options: &ExtractOptions,
+) -> Result<()>
+where
+    E: FnOnce(
+        &mut dyn FnMut(&Entry, &mut dyn Read) -> Result<()>,
+    ) -> Result<()>,
+{
+    let mut missing = members.map(<[PathBuf]>::to_vec);
+
+    entries(&mut |entry, data| {
+        if let Some(missing) = &mut missing {
+            if !missing.contains(&entry.path) {
+                return Ok(());
+            }
+
+            missing.retain(|m| *m != entry.path);
+        }
+
+        entry.unpack(destination, data, options)
+    })?;
+
+    missing.map_or(Ok(()), |m| not_found(archive, &m))
+}
+
+fn verify<R>(path: &Path, mut reader: R) -> Result<()>
+where
+    R: Read,
+{
+    let mut block = [0; 512];
+    let mut index = 0;
+    let mut offset = 0;
+
+    loop {
+        let filled = read_block(&mut reader, &mut block).map_err(|e| {
+            log::error!("{}:  offset {offset}:  {e}", path.display());
+            ExitCode::DataErr
+        })?;
+
+        if filled == 0 || block.iter().all(|b| *b == 0) {
+            return io::copy(&mut reader, &mut io::sink()).map(drop).map_err(
+                |e| {
+                    log::error!("{}:  offset {offset}:  {e}", path.display());
+                    ExitCode::DataErr
+                },
+            );
+        }
+
+        let header = Header::from_byte_slice(&block);
+        let name = String::from_utf8_lossy(&header.path_bytes()).into_owned();
+        let report = |reason: String| {
+            log::error!(
+                "{}:  entry {index} ('{name}') at offset {offset}:  {reason}",
+                path.display()
+            );
+            Err(ExitCode::DataErr)
+        };
+
+        if filled < block.len() {
+            return report(format!("truncated header ({filled} of 512 bytes)"));
+        }
+
+        let checksum = block
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                if (148..156).contains(&i) {
+                    32
+                } else {
+                    u32::from(*b)
+                }
+            })
+            .sum::<u32>();
+
+        match header.cksum() {
+            Ok(c) if c == checksum => {}
+            Ok(c) => {
+                return report(format!(
+                    "checksum mismatch (stored {c}, computed {checksum})"
+                ))
+            }
+            Err(e) => return report(e.to_string()),
+        }
+
+        let size = match header.entry_size() {
+            Ok(s) => s,
+            Err(e) => return report(e.to_string()),
+        };
+        let padded = size.div_ceil(512) * 512;
+        let skipped =
+            match io::copy(&mut (&mut reader).take(padded), &mut io::sink()) {
+                Ok(s) => s,
+                Err(e) => return report(e.to_string()),
+            };
+
+        if skipped < padded {
+            return report(format!(
+                "truncated data ({skipped} of {padded} bytes)"
+            ));
+        }
+
+        index += 1;
+        offset += 512 + padded;
+    }
+}
+
+#[cfg(unix)]
+fn xattrs(path: &Path) -> io::Result<Vec<u8>> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut records = Vec::new();
+    let names = match xattr::list_deref(path) {
+        Ok(names) => names,
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => return Ok(records),
+        Err(e) => return Err(e),
+    };
+
+    for name in names {
+        if let Some(value) = xattr::get_deref(path, &name)? {
+            pax_record(
+                &mut records,
+                &[XATTR_PREFIX, name.as_bytes()].concat(),
+                &value,
+            );
+        }
+    }
+
+    Ok(records)
+}
+
+#[cfg(not(unix))]
+fn xattrs(_: &Path) -> io::Result<Vec<u8>> {
+    Ok(Vec::new())
+}
+
+#[cfg(feature = "zip")]
+fn zip_error(error: zip::result::ZipError) -> ExitCode {
+    log::error!("{error}");
+
+    match error {
+        zip::result::ZipError::Io(e) => e.into(),
+        _ => ExitCode::DataErr,
+    }
+}
+
+#[cfg(feature = "zip")]
+fn zip_name(path: &Path) -> Result<String> {
+    let mut result = Vec::new();
+
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::Normal(c) => {
+                result.push(c.to_str().ok_or(ExitCode::DataErr)?);
+            }
+            _ => {
+                log::error!("paths in archives must be relative");
+                return Err(ExitCode::DataErr);
+            }
+        }
+    }
+
+    Ok(result.join("/"))
 }
 
 /******************************************************************************/
@@ -0,0 +1,547 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use crate::Dictionary;
+use brotli::enc::{
+    encode::{BrotliEncoderOperation, BrotliEncoderStateStruct},
+    StandardAlloc,
+};
+use std::{
+    io::{self, Read, Write},
+    path::Path,
+};
+
+const DICTIONARY_MARKER: &[u8] = b"tbr:dictionary:";
+
+/// The compression layers a TAR archive can be wrapped in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    /// Brotli, as used by TAR.BR and TBR archives.
+    Brotli,
+
+    /// Bzip2, as used by TAR.BZ2 and TBZ2 archives.
+    #[cfg(feature = "bzip2")]
+    Bzip2,
+
+    /// Gzip, as used by TAR.GZ and TGZ archives.
+    #[cfg(feature = "gzip")]
+    Gzip,
+
+    /// No compression at all, as used by plain TAR archives.
+    Uncompressed,
+
+    /// XZ, as used by TAR.XZ and TXZ archives.
+    #[cfg(feature = "xz")]
+    Xz,
+
+    /// Zstandard, as used by TAR.ZST and TZST archives.
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Detect the compression layer of the given path.
+    ///
+    /// Existing files will be identified by their magic bytes, if possible.
+    /// Since Brotli streams do not have any, the path's extension will be
+    /// considered, otherwise.  Paths without a known extension are considered
+    /// uncompressed.
+    pub fn detect<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(compression) = Self::sniff(path.as_ref()) {
+            return compression;
+        }
+
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("br" | "tbr") => Self::Brotli,
+            #[cfg(feature = "bzip2")]
+            Some("bz2" | "tb2" | "tbz" | "tbz2") => Self::Bzip2,
+            #[cfg(feature = "gzip")]
+            Some("gz" | "tgz") => Self::Gzip,
+            #[cfg(feature = "xz")]
+            Some("txz" | "xz") => Self::Xz,
+            #[cfg(feature = "zstd")]
+            Some("tzst" | "zst") => Self::Zstd,
+            _ => Self::Uncompressed,
+        }
+    }
+
+    pub(crate) fn reader<'a, R>(
+        self,
+        reader: R,
+        dictionary: Option<&Dictionary>,
+    ) -> io::Result<Box<dyn Read + 'a>>
+    where
+        R: Read + 'a,
+    {
+        Ok(match self {
+            Self::Brotli => brotli_reader(reader, dictionary)?,
+            #[cfg(feature = "bzip2")]
+            Self::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+            #[cfg(feature = "gzip")]
+            Self::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+            Self::Uncompressed => Box::new(reader),
+            #[cfg(feature = "xz")]
+            Self::Xz => {
+                Box::new(xz2::read::XzDecoder::new_multi_decoder(reader))
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => {
+                let mut decoder = zstd::Decoder::new(reader)?;
+                decoder.window_log_max(
+                    if cfg!(target_pointer_width = "64") {
+                        31
+                    } else {
+                        30
+                    },
+                )?;
+                Box::new(decoder)
+            }
+        })
+    }
+
+    /// Identify the compression layer of the given file by its magic bytes.
+    ///
+    /// This method will return [`None`] in case that the file cannot be read
+    /// or does not start with the magic bytes of a supported compression layer.
+    #[must_use]
+    pub fn sniff<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut magic = Vec::with_capacity(10);
+
+        crate::volume::open(path.as_ref())
+            .ok()?
+            .take(10)
+            .read_to_end(&mut magic)
+            .ok()?;
+
+        match magic.as_slice() {
+            #[cfg(feature = "bzip2")]
+            [b'B', b'Z', b'h', b'1'..=b'9', 0x31, 0x41, 0x59, 0x26, 0x53, 0x59] => {
+                Some(Self::Bzip2)
+            }
+            #[cfg(feature = "gzip")]
+            [0x1f, 0x8b, ..] => Some(Self::Gzip),
+            #[cfg(feature = "xz")]
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => Some(Self::Xz),
+            #[cfg(feature = "zstd")]
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn writer<W>(
+        self,
+        writer: W,
+        tuning: &Tuning,
+    ) -> io::Result<Encoder<W>>
+    where
+        W: Write,
+    {
+        Ok(match self {
+            Self::Brotli if tuning.seekable => {
+                if tuning.dictionary.is_some() {
+                    log::error!(
+                        "Seekable archives do not support custom dictionaries."
+                    );
+                    return Err(io::ErrorKind::Unsupported.into());
+                }
+
+                Encoder::Seekable(Box::new(crate::seekable::Writer::new(
+                    writer,
+                    tuning.level.map_or(11, |l| l.clamp(0, 11)),
+                )?))
+            }
+            Self::Brotli => Encoder::Brotli(Box::new(BrotliWriter::new(
+                writer,
+                tuning.level.map_or(11, |l| l.clamp(0, 11)),
+                tuning.dictionary.as_ref(),
+            )?)),
+            #[cfg(feature = "bzip2")]
+            Self::Bzip2 => Encoder::Bzip2(bzip2::write::BzEncoder::new(
+                writer,
+                tuning.level.map_or_else(bzip2::Compression::best, |l| {
+                    bzip2::Compression::new(l.clamp(1, 9).unsigned_abs())
+                }),
+            )),
+            #[cfg(feature = "gzip")]
+            Self::Gzip => Encoder::Gzip(flate2::write::GzEncoder::new(
+                writer,
+                tuning.level.map_or_else(flate2::Compression::best, |l| {
+                    flate2::Compression::new(l.clamp(0, 9).unsigned_abs())
+                }),
+            )),
+            Self::Uncompressed => Encoder::Uncompressed(writer),
+            #[cfg(feature = "xz")]
+            Self::Xz => Encoder::Xz(xz2::write::XzEncoder::new(
+                writer,
+                tuning.level.map_or(6, |l| l.clamp(0, 9).unsigned_abs()),
+            )),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => {
+                let mut encoder = zstd::Encoder::new(
+                    writer,
+                    tuning.level.unwrap_or(zstd::DEFAULT_COMPRESSION_LEVEL),
+                )?;
+
+                if let Some(window) = tuning.window {
+                    encoder.long_distance_matching(true)?;
+                    encoder.window_log(window)?;
+                }
+
+                Encoder::Zstd(encoder)
+            }
+        })
+    }
+}
+
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Brotli => "Brotli",
+                #[cfg(feature = "bzip2")]
+                Self::Bzip2 => "Bzip2",
+                #[cfg(feature = "gzip")]
+                Self::Gzip => "Gzip",
+                Self::Uncompressed => "uncompressed",
+                #[cfg(feature = "xz")]
+                Self::Xz => "XZ",
+                #[cfg(feature = "zstd")]
+                Self::Zstd => "Zstandard",
+            }
+        )
+    }
+}
+
+pub enum Encoder<W>
+where
+    W: Write,
+{
+    Brotli(Box<BrotliWriter<W>>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(bzip2::write::BzEncoder<W>),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<W>),
+    Seekable(Box<crate::seekable::Writer<W>>),
+    Uncompressed(W),
+    #[cfg(feature = "xz")]
+    Xz(xz2::write::XzEncoder<W>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W> Encoder<W>
+where
+    W: Write,
+{
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Brotli(w) => w.finish(),
+            #[cfg(feature = "bzip2")]
+            Self::Bzip2(w) => w.finish(),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(w) => w.finish(),
+            Self::Seekable(w) => w.finish(),
+            Self::Uncompressed(mut w) => {
+                w.flush()?;
+                Ok(w)
+            }
+            #[cfg(feature = "xz")]
+            Self::Xz(w) => w.finish(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(w) => w.finish(),
+        }
+    }
+}
+
+impl<W> Write for Encoder<W>
+where
+    W: Write,
+{
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Brotli(w) => w.flush(),
+            #[cfg(feature = "bzip2")]
+            Self::Bzip2(w) => w.flush(),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(w) => w.flush(),
+            Self::Seekable(w) => w.flush(),
+            Self::Uncompressed(w) => w.flush(),
+            #[cfg(feature = "xz")]
+            Self::Xz(w) => w.flush(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(w) => w.flush(),
+        }
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Brotli(w) => w.write(buf),
+            #[cfg(feature = "bzip2")]
+            Self::Bzip2(w) => w.write(buf),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(w) => w.write(buf),
+            Self::Seekable(w) => w.write(buf),
+            Self::Uncompressed(w) => w.write(buf),
+            #[cfg(feature = "xz")]
+            Self::Xz(w) => w.write(buf),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(w) => w.write(buf),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Tuning {
+    pub dictionary: Option<Dictionary>,
+    pub level: Option<i32>,
+    pub seekable: bool,
+    pub window: Option<u32>,
+}
+
+pub struct BrotliWriter<W>
+where
+    W: Write,
+{
+    buffer: Vec<u8>,
+    state: BrotliEncoderStateStruct<StandardAlloc>,
+    writer: W,
+}
+
+impl<W> BrotliWriter<W>
+where
+    W: Write,
+{
+    /// Create an encoder whose output does not refer to any preceding data.
+    ///
+    /// Such streams can be decoded independently even when being placed in
+    /// the middle of another stream with the same window size.
+    pub fn catable(writer: W, quality: i32, window: i32) -> Self {
+        let mut result = Self {
+            buffer: vec![0; 4096],
+            state: BrotliEncoderStateStruct::new(StandardAlloc::default()),
+            writer,
+        };
+
+        result.state.params.appendable = true;
+        result.state.params.catable = true;
+        result.state.params.lgwin = window;
+        result.state.params.quality = quality;
+        result.state.params.use_dictionary = false;
+        result
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.process(BrotliEncoderOperation::BROTLI_OPERATION_FINISH, &[])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn new(
+        writer: W,
+        quality: i32,
+        dictionary: Option<&Dictionary>,
+    ) -> io::Result<Self> {
+        let mut result = Self {
+            buffer: vec![0; 4096],
+            state: BrotliEncoderStateStruct::new(StandardAlloc::default()),
+            writer,
+        };
+
+        result.state.params.quality = quality;
+
+        if let Some(dictionary) = dictionary {
+            result.process(
+                BrotliEncoderOperation::BROTLI_OPERATION_EMIT_METADATA,
+                &[DICTIONARY_MARKER, dictionary.id().as_bytes()].concat(),
+            )?;
+            result.state.set_custom_dictionary(
+                dictionary.as_bytes().len(),
+                dictionary.as_bytes(),
+            );
+        }
+
+        Ok(result)
+    }
+
+    fn process(
+        &mut self,
+        operation: BrotliEncoderOperation,
+        input: &[u8],
+    ) -> io::Result<()> {
+        let mut available_in = input.len();
+        let mut input_offset = 0;
+
+        loop {
+            let mut available_out = self.buffer.len();
+            let mut output_offset = 0;
+
+            if !self.state.compress_stream(
+                operation,
+                &mut available_in,
+                input,
+                &mut input_offset,
+                &mut available_out,
+                &mut self.buffer,
+                &mut output_offset,
+                &mut None,
+                &mut |_, _, _, _| (),
+            ) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Brotli encoder failure",
+                ));
+            }
+
+            self.writer.write_all(&self.buffer[..output_offset])?;
+
+            let done = match operation {
+                BrotliEncoderOperation::BROTLI_OPERATION_FINISH => {
+                    self.state.is_finished()
+                }
+                _ => available_in == 0 && !self.state.has_more_output(),
+            };
+
+            if done {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<W> Write for BrotliWriter<W>
+where
+    W: Write,
+{
+    fn flush(&mut self) -> io::Result<()> {
+        self.process(BrotliEncoderOperation::BROTLI_OPERATION_FLUSH, &[])?;
+        self.writer.flush()
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.process(BrotliEncoderOperation::BROTLI_OPERATION_PROCESS, buf)?;
+        Ok(buf.len())
+    }
+}
+
+fn brotli_reader<'a, R>(
+    mut reader: R,
+    dictionary: Option<&Dictionary>,
+) -> io::Result<Box<dyn Read + 'a>>
+where
+    R: Read + 'a,
+{
+    let mut prefix = Vec::new();
+    let id = brotli_metadata(&mut reader, &mut prefix)?
+        .as_deref()
+        .and_then(|m| m.strip_prefix(DICTIONARY_MARKER))
+        .map(|id| String::from_utf8_lossy(id).to_string());
+    let reader = io::Cursor::new(prefix).chain(reader);
+
+    match (id, dictionary) {
+        (None, _) => Ok(Box::new(brotli::Decompressor::new(reader, 4096))),
+        (Some(id), Some(dictionary)) if id == dictionary.id() => {
+            Ok(Box::new(brotli::Decompressor::new_with_custom_dict(
+                reader,
+                4096,
+                dictionary.as_bytes().to_vec().into(),
+            )))
+        }
+        (Some(id), _) => {
+            log::error!("This stream requires the Brotli dictionary {id}.");
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "missing Brotli dictionary",
+            ))
+        }
+    }
+}
+
+/// Read the leading metadata block of a Brotli stream, if any.
+///
+/// All bytes consumed from the reader will be appended to the given prefix
+/// such that the stream can be reassembled afterwards.
+fn brotli_metadata<R>(
+    reader: &mut R,
+    prefix: &mut Vec<u8>,
+) -> io::Result<Option<Vec<u8>>>
+where
+    R: Read,
+{
+    reader.take(8).read_to_end(prefix)?;
+
+    let bit = |index: usize| {
+        prefix
+            .get(index / 8)
+            .map(|b| u64::from(b >> (index % 8) & 1))
+    };
+    let bits = |start: usize, count: usize| {
+        (0..count).try_fold(0, |value, i| Some(value | bit(start + i)? << i))
+    };
+    let Some(mut position) = (match bit(0) {
+        Some(0) => Some(1),
+        Some(_) if bits(1, 3) != Some(0) => Some(4),
+        Some(_) => Some(7),
+        None => None,
+    }) else {
+        return Ok(None);
+    };
+
+    if bits(position, 3) != Some(0b110) {
+        return Ok(None);
+    }
+
+    position += 4;
+
+    let Some(length_bytes) = bits(position, 2) else {
+        return Ok(None);
+    };
+    let Some(length) = bits(
+        position + 2,
+        8 * usize::try_from(length_bytes).unwrap_or_default(),
+    )
+    .map(|l| if length_bytes == 0 { 0 } else { l + 1 }) else {
+        return Ok(None);
+    };
+
+    if length > 1024 {
+        return Ok(None);
+    }
+
+    let start =
+        (position + 2 + 8 * usize::try_from(length_bytes).unwrap_or_default())
+            .div_ceil(8);
+    let end = start + usize::try_from(length).unwrap_or_default();
+
+    if prefix.len() < end {
+        let missing = u64::try_from(end - prefix.len()).unwrap_or_default();
+        reader.take(missing).read_to_end(prefix)?;
+    }
+
+    Ok(prefix.get(start..end).map(<[u8]>::to_vec))
+}
+
+/******************************************************************************/
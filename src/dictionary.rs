@@ -0,0 +1,181 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
+use sysexits::{ExitCode, Result};
+
+/// A custom Brotli dictionary.
+///
+/// Brotli streams compressed with a custom dictionary can only be decompressed
+/// with the very same dictionary.  Hence, such streams will start with a
+/// metadata block naming the dictionary's [`Dictionary::id`], which will be
+/// skipped by any other Brotli decoder.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dictionary {
+    bytes: Arc<[u8]>,
+}
+
+impl Dictionary {
+    /// The raw content of this dictionary.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The identifier of this dictionary, its hexadecimal BLAKE3 digest.
+    #[must_use]
+    pub fn id(&self) -> String {
+        blake3::hash(&self.bytes).to_hex().to_string()
+    }
+
+    /// Create a new instance from the given raw content.
+    #[must_use]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Read a dictionary from the file system.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn read<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::new(std::fs::read(path)?))
+    }
+
+    /// Train a dictionary of at most the given size on the given sample files.
+    ///
+    /// The samples will be split into as many epochs as segments fit into the
+    /// dictionary.  From each epoch, the segment sharing the most substrings
+    /// with the other samples will be chosen.  The most valuable segments will
+    /// be placed at the end of the dictionary since they are the cheapest to
+    /// refer to.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn train<P>(samples: &[P], size: usize) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        const KMER: usize = 8;
+        const SEGMENT: usize = 256;
+
+        let mut data = Vec::new();
+
+        for sample in samples {
+            data.push(std::fs::read(sample)?);
+        }
+
+        let mut frequency = HashMap::<&[u8], u64>::new();
+
+        for sample in &data {
+            let mut seen = HashSet::new();
+
+            for kmer in sample.windows(KMER) {
+                if seen.insert(kmer) {
+                    *frequency.entry(kmer).or_default() += 1;
+                }
+            }
+        }
+
+        if frequency.is_empty() || size < SEGMENT {
+            log::error!(
+                "Please provide larger samples and a dictionary size of at \
+                 least {SEGMENT} bytes."
+            );
+            return Err(ExitCode::Usage);
+        }
+
+        let data = data.concat();
+        let epochs = (size / SEGMENT).min(data.len() / SEGMENT).max(1);
+        let mut segments = Vec::new();
+
+        for epoch in data.chunks(data.len().div_ceil(epochs)) {
+            let kmers = epoch.windows(KMER).collect::<Vec<_>>();
+            let span = epoch.len().min(SEGMENT) + 1 - KMER.min(epoch.len());
+            let mut active = HashMap::<&[u8], usize>::new();
+            let mut best = (0, 0);
+            let mut score = 0;
+
+            for (index, kmer) in kmers.iter().enumerate() {
+                let count = active.entry(kmer).or_default();
+
+                if *count == 0 {
+                    score += frequency.get(kmer).copied().unwrap_or_default();
+                }
+
+                *count += 1;
+
+                if index >= span {
+                    let old = kmers[index - span];
+                    let count = active.entry(old).or_default();
+                    *count -= 1;
+
+                    if *count == 0 {
+                        score -=
+                            frequency.get(old).copied().unwrap_or_default();
+                    }
+                }
+
+                if index + 1 >= span && score > best.1 {
+                    best = (index + 1 - span, score);
+                }
+            }
+
+            if best.1 > 0 {
+                let segment =
+                    &epoch[best.0..(best.0 + SEGMENT).min(epoch.len())];
+
+                for kmer in segment.windows(KMER) {
+                    frequency.remove(kmer);
+                }
+
+                segments.push(segment);
+            }
+        }
+
+        segments.reverse();
+
+        Ok(Self::new(segments.concat()))
+    }
+
+    /// Write this dictionary to the file system.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn write<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(std::fs::write(path, &self.bytes)?)
+    }
+}
+
+/******************************************************************************/
@@ -0,0 +1,335 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use crate::{Digest, Tar};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::File,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+use sysexits::Result;
+
+/// A single difference between two archives.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Change {
+    /// The member only exists in the new archive.
+    Added(PathBuf),
+
+    /// The member exists in both archives but differs in the given properties.
+    Modified(PathBuf, Vec<Property>),
+
+    /// The member only exists in the old archive.
+    Removed(PathBuf),
+}
+
+impl Change {
+    /// The path of the affected member.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Added(p) | Self::Modified(p, _) | Self::Removed(p) => p,
+        }
+    }
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added(p) => write!(f, "added     {}", p.display()),
+            Self::Modified(p, properties) => write!(
+                f,
+                "modified  {} ({})",
+                p.display(),
+                properties
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::Removed(p) => write!(f, "removed   {}", p.display()),
+        }
+    }
+}
+
+/// The differences between two archives or an archive and a directory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Difference {
+    changes: Vec<Change>,
+}
+
+impl Difference {
+    /// The changes, sorted by path.
+    #[must_use]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Whether both sides are equal.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Compare the given sides.
+    ///
+    /// Each side is either a TAR archive or a directory.  Their members will be
+    /// compared by path, size, mode, modification time, and content hash.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn new<P, Q>(old: P, new: Q) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let old = snapshot(old.as_ref())?;
+        let new = snapshot(new.as_ref())?;
+        let mut changes = BTreeMap::new();
+
+        for (path, member) in &old {
+            match new.get(path) {
+                Some(m) => {
+                    let properties = member.compare(m);
+
+                    if !properties.is_empty() {
+                        changes.insert(
+                            path,
+                            Change::Modified(path.clone(), properties),
+                        );
+                    }
+                }
+                None => {
+                    changes.insert(path, Change::Removed(path.clone()));
+                }
+            }
+        }
+
+        for path in new.keys() {
+            if !old.contains_key(path) {
+                changes.insert(path, Change::Added(path.clone()));
+            }
+        }
+
+        Ok(Self {
+            changes: changes.into_values().collect(),
+        })
+    }
+
+    /// Render these differences as a JSON object.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let list = |f: fn(&Change) -> bool| {
+            self.changes
+                .iter()
+                .filter(|c| f(c))
+                .map(|c| json_string(c.path()))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let modified = self
+            .changes
+            .iter()
+            .filter_map(|c| match c {
+                Change::Modified(p, properties) => Some(format!(
+                    "{{\"path\":{},\"changes\":[{}]}}",
+                    json_string(p),
+                    properties
+                        .iter()
+                        .map(|p| format!("\"{p}\""))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{{\"added\":[{}],\"removed\":[{}],\"modified\":[{modified}]}}",
+            list(|c| matches!(c, Change::Added(_))),
+            list(|c| matches!(c, Change::Removed(_))),
+        )
+    }
+}
+
+impl std::fmt::Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The properties a member can differ in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Property {
+    /// The content hash.
+    Content,
+
+    /// The permission bits.
+    Mode,
+
+    /// The modification time.
+    Mtime,
+
+    /// The size.
+    Size,
+}
+
+impl std::fmt::Display for Property {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Content => "content",
+                Self::Mode => "mode",
+                Self::Mtime => "mtime",
+                Self::Size => "size",
+            }
+        )
+    }
+}
+
+pub struct Member {
+    pub path: PathBuf,
+    pub size: u64,
+    pub mode: u32,
+    pub mtime: u64,
+    pub hash: Option<String>,
+}
+
+impl Member {
+    fn compare(&self, other: &Self) -> Vec<Property> {
+        let mut result = Vec::new();
+
+        if self.size != other.size {
+            result.push(Property::Size);
+        }
+
+        if self.mode != other.mode {
+            result.push(Property::Mode);
+        }
+
+        if self.mtime != other.mtime {
+            result.push(Property::Mtime);
+        }
+
+        if self.hash != other.hash {
+            result.push(Property::Content);
+        }
+
+        result
+    }
+}
+
+fn json_string(path: &Path) -> String {
+    let mut result = String::from("\"");
+
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(result, "\\u{:04x}", u32::from(c));
+            }
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+fn snapshot(path: &Path) -> Result<BTreeMap<PathBuf, Member>> {
+    if path.is_dir() {
+        let mut result = BTreeMap::new();
+        walk(path, Path::new(""), &mut result)?;
+        Ok(result)
+    } else {
+        Ok(Tar::new(path)
+            .members()?
+            .into_iter()
+            .map(|m| (m.path.clone(), m))
+            .collect())
+    }
+}
+
+fn walk(
+    root: &Path,
+    relative: &Path,
+    members: &mut BTreeMap<PathBuf, Member>,
+) -> Result<()> {
+    for entry in root.join(relative).read_dir()? {
+        let path = relative.join(entry?.file_name());
+        let metadata = root.join(&path).symlink_metadata()?;
+        let hash = if metadata.is_file() {
+            Some(Digest::Blake3.hash(File::open(root.join(&path))?)?)
+        } else {
+            None
+        };
+
+        members.insert(
+            path.clone(),
+            Member {
+                path: path.clone(),
+                size: if metadata.is_file() {
+                    metadata.len()
+                } else {
+                    0
+                },
+                mode: mode(&metadata),
+                mtime: metadata
+                    .modified()?
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs()),
+                hash,
+            },
+        );
+
+        if metadata.is_dir() {
+            walk(root, &path, members)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+pub fn mode(metadata: &std::fs::Metadata) -> u32 {
+    if metadata.permissions().readonly() {
+        0o444
+    } else {
+        0o644
+    }
+}
+
+/******************************************************************************/
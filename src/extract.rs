@@ -0,0 +1,463 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use crate::Action;
+use std::{
+    fs::{remove_dir_all, remove_file, rename},
+    io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+/// The settings to restore the members' metadata with when extracting an
+/// archive.
+///
+/// By default, the permission bits will be restored without the special bits,
+/// the modification times will be restored, existing files will be replaced,
+/// and all files will be owned by the extracting user.  Without a confirmation
+/// callback, asking whether to replace an existing file will keep it.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Copy, Debug)]
+pub struct ExtractOptions {
+    confirm: Option<fn(&Path) -> io::Result<bool>>,
+    mask: u32,
+    mtime: bool,
+    numeric_owner: bool,
+    overwrite: Overwrite,
+    ownership: bool,
+    permissions: bool,
+}
+
+impl ExtractOptions {
+    /// The callback deciding whether to replace an existing file when asking.
+    #[must_use]
+    pub const fn confirm(&self) -> Option<fn(&Path) -> io::Result<bool>> {
+        self.confirm
+    }
+
+    /// The permission bits to clear from the extracted files, similar to a
+    /// `umask`.
+    #[must_use]
+    pub const fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// The permission bits to apply to an extracted file with the given mode.
+    #[must_use]
+    pub(crate) const fn mode(&self, mode: u32) -> u32 {
+        if self.permissions {
+            mode & 0o7777 & !self.mask
+        } else {
+            mode & 0o777 & !self.mask
+        }
+    }
+
+    /// Whether to restore the modification times.
+    #[must_use]
+    pub const fn mtime(&self) -> bool {
+        self.mtime
+    }
+
+    /// Whether to restore the owners by their numeric IDs only, instead of
+    /// looking up their names first.
+    #[must_use]
+    pub const fn numeric_owner(&self) -> bool {
+        self.numeric_owner
+    }
+
+    /// How to treat files which already exist in the destination.
+    #[must_use]
+    pub const fn overwrite(&self) -> Overwrite {
+        self.overwrite
+    }
+
+    /// Whether to restore the owners.
+    #[must_use]
+    pub const fn ownership(&self) -> bool {
+        self.ownership
+    }
+
+    /// Whether to restore the permission bits including the setuid, setgid,
+    /// and sticky bits.
+    #[must_use]
+    pub const fn permissions(&self) -> bool {
+        self.permissions
+    }
+
+    /// The action extracting the given file would take according to the
+    /// overwrite policy, given the modification time of the member to extract.
+    ///
+    /// # Errors
+    ///
+    /// See [`std::io::Error`].
+    pub(crate) fn plan(&self, path: &Path, mtime: u64) -> io::Result<Action> {
+        let Ok(metadata) = path.symlink_metadata() else {
+            return Ok(Action::Extract(path.to_path_buf()));
+        };
+
+        match self.overwrite {
+            Overwrite::Fail => Ok(Action::Fail(path.to_path_buf())),
+            Overwrite::KeepNewer
+                if metadata.modified()?
+                    > UNIX_EPOCH + std::time::Duration::from_secs(mtime) =>
+            {
+                Ok(Action::Skip(path.to_path_buf()))
+            }
+            Overwrite::Skip => Ok(Action::Skip(path.to_path_buf())),
+            _ => Ok(Action::Replace(path.to_path_buf())),
+        }
+    }
+
+    /// Make room for the given file to extract according to the overwrite
+    /// policy.
+    ///
+    /// This method will return whether the file shall be extracted, given the
+    /// modification time of the member to extract.  A directory in the way of
+    /// the file will be replaced together with its contents.
+    ///
+    /// # Errors
+    ///
+    /// See [`std::io::Error`].
+    pub(crate) fn prepare(&self, path: &Path, mtime: u64) -> io::Result<bool> {
+        let replace = match self.plan(path, mtime)? {
+            Action::Replace(_) => match self.overwrite {
+                Overwrite::Ask => match self.confirm {
+                    Some(confirm) => confirm(path)?,
+                    None => false,
+                },
+                Overwrite::Rename => {
+                    let backup = backup(path);
+
+                    log::debug!(
+                        "{}:  moving aside to {}",
+                        path.display(),
+                        backup.display()
+                    );
+                    rename(path, backup)?;
+                    return Ok(true);
+                }
+                _ => true,
+            },
+            Action::Fail(_) => {
+                log::error!("{}:  already exists", path.display());
+                return Err(io::ErrorKind::AlreadyExists.into());
+            }
+            Action::Skip(_) => false,
+            _ => return Ok(true),
+        };
+
+        if replace {
+            log::debug!("{}:  replacing", path.display());
+
+            if path.symlink_metadata()?.is_dir() {
+                remove_dir_all(path)?;
+            } else {
+                remove_file(path)?;
+            }
+        } else {
+            log::debug!("{}:  skipping, already exists", path.display());
+        }
+
+        Ok(replace)
+    }
+
+    /// Restore the owner of the given file, if requested.
+    ///
+    /// The owner will be looked up by the given names, falling back to the
+    /// numeric IDs, unless only the numeric IDs should be considered.
+    ///
+    /// # Errors
+    ///
+    /// See [`std::io::Error`].
+    pub(crate) fn restore_owner(
+        &self,
+        path: &Path,
+        ids: (u32, u32),
+        names: (Option<&str>, Option<&str>),
+    ) -> io::Result<()> {
+        if !self.ownership {
+            return Ok(());
+        }
+
+        let (uid, gid) = if self.numeric_owner {
+            ids
+        } else {
+            (
+                names.0.and_then(uid).unwrap_or(ids.0),
+                names.1.and_then(gid).unwrap_or(ids.1),
+            )
+        };
+
+        chown(path, uid, gid)
+    }
+
+    /// Set the callback deciding whether to replace an existing file when
+    /// asking.
+    #[must_use]
+    pub const fn with_confirm(
+        mut self,
+        confirm: fn(&Path) -> io::Result<bool>,
+    ) -> Self {
+        self.confirm = Some(confirm);
+        self
+    }
+
+    /// Set the permission bits to clear from the extracted files.
+    #[must_use]
+    pub const fn with_mask(mut self, mask: u32) -> Self {
+        self.mask = mask & 0o7777;
+        self
+    }
+
+    /// Set whether to restore the modification times.
+    #[must_use]
+    pub const fn with_mtime(mut self, mtime: bool) -> Self {
+        self.mtime = mtime;
+        self
+    }
+
+    /// Set whether to restore the owners by their numeric IDs only.
+    #[must_use]
+    pub const fn with_numeric_owner(mut self, numeric_owner: bool) -> Self {
+        self.numeric_owner = numeric_owner;
+        self
+    }
+
+    /// Set how to treat files which already exist in the destination.
+    #[must_use]
+    pub const fn with_overwrite(mut self, overwrite: Overwrite) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Set whether to restore the owners.
+    ///
+    /// This usually requires superuser privileges.
+    #[must_use]
+    pub const fn with_ownership(mut self, ownership: bool) -> Self {
+        self.ownership = ownership;
+        self
+    }
+
+    /// Set whether to restore the permission bits including the setuid,
+    /// setgid, and sticky bits.
+    #[must_use]
+    pub const fn with_permissions(mut self, permissions: bool) -> Self {
+        self.permissions = permissions;
+        self
+    }
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            confirm: None,
+            mask: 0,
+            mtime: true,
+            numeric_owner: false,
+            overwrite: Overwrite::default(),
+            ownership: false,
+            permissions: false,
+        }
+    }
+}
+
+/// The ways to treat files which already exist in the destination when
+/// extracting an archive.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Overwrite {
+    /// Ask the confirmation callback whether to replace each existing file.
+    Ask,
+
+    /// Refuse to replace any existing file.
+    Fail,
+
+    /// Replace existing files unless they are newer than their members.
+    KeepNewer,
+
+    /// Move existing files aside by appending a numbered suffix, such as
+    /// `file.~1~`.
+    Rename,
+
+    /// Replace existing files.
+    #[default]
+    Replace,
+
+    /// Keep existing files and skip their members.
+    Skip,
+}
+
+impl std::fmt::Display for Overwrite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Ask => "ask",
+                Self::Fail => "fail",
+                Self::KeepNewer => "keep newer",
+                Self::Rename => "rename",
+                Self::Replace => "replace",
+                Self::Skip => "skip",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for Overwrite {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "ask" | "interactive" | "prompt" => Ok(Self::Ask),
+            "error" | "fail" | "keep-old-files" => Ok(Self::Fail),
+            "keep-newer" | "keep-newer-files" | "newer" => Ok(Self::KeepNewer),
+            "backup" | "rename" | "suffix" => Ok(Self::Rename),
+            "overwrite" | "replace" => Ok(Self::Replace),
+            "skip" | "skip-old-files" => Ok(Self::Skip),
+            _ => Err(format!("'{s}' is not supported, yet")),
+        }
+    }
+}
+
+/// Ensure that the parent directory of the given destination path resolves
+/// inside the destination.
+///
+/// None of the parent directories below the destination may be a symbolic
+/// link, as a member extracted earlier could, otherwise, redirect the file
+/// outside the destination.
+///
+/// # Errors
+///
+/// See [`std::io::Error`].
+pub fn confine(destination: &Path, path: &Path) -> io::Result<()> {
+    let root = match destination.canonicalize() {
+        Ok(root) => root,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let mut current = destination.to_path_buf();
+    let mut existing = root.clone();
+    let Some(parents) = path
+        .strip_prefix(destination)
+        .map_err(|_| escape(path))?
+        .parent()
+    else {
+        return Ok(());
+    };
+
+    for component in parents.components() {
+        current.push(component);
+
+        match current.symlink_metadata() {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(escape(path));
+            }
+            Ok(_) => existing.clone_from(&current),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    if existing.canonicalize()?.starts_with(&root) {
+        Ok(())
+    } else {
+        Err(escape(path))
+    }
+}
+
+/// The destination path of the given member, with all components removed which
+/// would not be extracted.
+///
+/// Members escaping the destination do not have a destination path.
+pub fn destination(destination: &Path, member: &Path) -> Option<PathBuf> {
+    let mut result = destination.to_path_buf();
+
+    for component in member.components() {
+        match component {
+            std::path::Component::Normal(c) => result.push(c),
+            std::path::Component::ParentDir => return None,
+            _ => {}
+        }
+    }
+
+    Some(result)
+}
+
+fn backup(path: &Path) -> PathBuf {
+    (1..=usize::MAX)
+        .map(|n| {
+            let mut result = path.as_os_str().to_owned();
+            result.push(format!(".~{n}~"));
+            PathBuf::from(result)
+        })
+        .find(|p| p.symlink_metadata().is_err())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn escape(path: &Path) -> io::Error {
+    log::error!(
+        "{}:  escapes the destination through a symbolic link",
+        path.display()
+    );
+    io::ErrorKind::InvalidData.into()
+}
+
+#[cfg(unix)]
+fn chown(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
+    use rustix::{
+        fs::{chownat, AtFlags, CWD},
+        process::{Gid, Uid},
+    };
+
+    Ok(chownat(
+        CWD,
+        path,
+        Some(Uid::from_raw(uid)),
+        Some(Gid::from_raw(gid)),
+        AtFlags::SYMLINK_NOFOLLOW,
+    )?)
+}
+
+#[cfg(not(unix))]
+fn chown(_: &Path, _: u32, _: u32) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn gid(name: &str) -> Option<u32> {
+    Some(nix::unistd::Group::from_name(name).ok()??.gid.as_raw())
+}
+
+#[cfg(not(unix))]
+const fn gid(_: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn uid(name: &str) -> Option<u32> {
+    Some(nix::unistd::User::from_name(name).ok()??.uid.as_raw())
+}
+
+#[cfg(not(unix))]
+const fn uid(_: &str) -> Option<u32> {
+    None
+}
+
+/******************************************************************************/
@@ -95,18 +95,66 @@
 //!
 //! ## Dependencies
 //!
+//! - [`blake3`]
+//!   [![](https://img.shields.io/crates/l/blake3)
+//!   ](https://github.com/BLAKE3-team/BLAKE3)
+//!
 //! - [`brotli`]
 //!   [![](https://img.shields.io/crates/l/brotli)
 //!   ](https://github.com/dropbox/rust-brotli)
 //!
+//! - [`bzip2`]
+//!   [![](https://img.shields.io/crates/l/bzip2)
+//!   ](https://github.com/trifectatechfoundation/bzip2-rs)
+//!
+//! - [`chrono`]
+//!   [![](https://img.shields.io/crates/l/chrono)
+//!   ](https://github.com/chronotope/chrono)
+//!
 //! - [`clap`]
 //!   [![](https://img.shields.io/crates/l/clap)
 //!   ](https://github.com/clap-rs/clap)
 //!
+//! - [`clap_complete`]
+//!   [![](https://img.shields.io/crates/l/clap_complete)
+//!   ](https://github.com/clap-rs/clap)
+//!
+//! - [`clap_mangen`]
+//!   [![](https://img.shields.io/crates/l/clap_mangen)
+//!   ](https://github.com/clap-rs/clap)
+//!
+//! - [`env_logger`]
+//!   [![](https://img.shields.io/crates/l/env_logger)
+//!   ](https://github.com/rust-cli/env_logger)
+//!
+//! - [`flate2`]
+//!   [![](https://img.shields.io/crates/l/flate2)
+//!   ](https://github.com/rust-lang/flate2-rs)
+//!
+//! - [`fs4`]
+//!   [![](https://img.shields.io/crates/l/fs4)
+//!   ](https://github.com/al8n/fs4-rs)
+//!
 //! - [`glob`]
 //!   [![](https://img.shields.io/crates/l/glob)
 //!   ](https://github.com/rust-lang/glob)
 //!
+//! - [`log`]
+//!   [![](https://img.shields.io/crates/l/log)
+//!   ](https://github.com/rust-lang/log)
+//!
+//! - [`nix`]
+//!   [![](https://img.shields.io/crates/l/nix)
+//!   ](https://github.com/nix-rust/nix)
+//!
+//! - [`rustix`]
+//!   [![](https://img.shields.io/crates/l/rustix)
+//!   ](https://github.com/bytecodealliance/rustix)
+//!
+//! - [`sha2`]
+//!   [![](https://img.shields.io/crates/l/sha2)
+//!   ](https://github.com/RustCrypto/hashes)
+//!
 //! - [`sysexits`]
 //!   [![](https://img.shields.io/crates/l/sysexits)
 //!   ](https://github.com/sorairolake/sysexits-rs)
@@ -119,6 +167,22 @@
 //!   [![](https://img.shields.io/crates/l/tempfile)
 //!   ](https://github.com/Stebalien/tempfile)
 //!
+//! - [`xattr`]
+//!   [![](https://img.shields.io/crates/l/xattr)
+//!   ](https://github.com/Stebalien/xattr)
+//!
+//! - [`xz2`]
+//!   [![](https://img.shields.io/crates/l/xz2)
+//!   ](https://github.com/alexcrichton/xz2-rs)
+//!
+//! - [`zip`]
+//!   [![](https://img.shields.io/crates/l/zip)
+//!   ](https://github.com/zip-rs/zip2)
+//!
+//! - [`zstd`]
+//!   [![](https://img.shields.io/crates/l/zstd)
+//!   ](https://github.com/gyscos/zstd-rs)
+//!
 //! <!------------------------------------------------------------------------->
 
 #![doc(
@@ -151,9 +215,27 @@
 
 mod application;
 mod archive;
+mod compression;
+mod dictionary;
+mod diff;
+mod extract;
+mod lock;
+mod manifest;
+mod plan;
+mod seekable;
+mod volume;
 
 pub use application::{Application, Mode as ApplicationMode};
-pub use archive::{Brotli, Tar};
+#[cfg(feature = "zip")]
+pub use archive::Zip;
+pub use archive::{Ar, Brotli, Conflict, Cpio, Tar};
+pub use compression::Compression;
+pub use dictionary::Dictionary;
+pub use diff::{Change, Difference, Property};
+pub use extract::{ExtractOptions, Overwrite};
+pub use lock::Locking;
+pub use manifest::{Digest, Manifest};
+pub use plan::{Action, Plan};
 
 /// This crate's name.
 pub const NAME: &str = "aeruginous-tbr";
@@ -0,0 +1,155 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use fs4::{FileExt, TryLockError};
+use std::{
+    fs::{File, OpenOptions},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use sysexits::{ExitCode, Result};
+
+/// An advisory lock of an archive which will be released when dropped.
+///
+/// The lock is held on a hidden file next to the archive, such as
+/// `.archive.tar.lock`, since the archive itself will be replaced when being
+/// rewritten.  The lock file will be removed while the lock is still held;
+/// processes which were waiting for it will then retry with a new one.
+pub struct Lock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl Lock {
+    /// Acquire the lock of the given archive.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn acquire(path: &Path, locking: Locking) -> Result<Self> {
+        let lock = name(path);
+        let start = Instant::now();
+
+        loop {
+            let file = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&lock)?;
+
+            wait(&file, path, locking, start)?;
+
+            if current(&file, &lock) {
+                return Ok(Self {
+                    _file: file,
+                    path: lock,
+                });
+            }
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            log::debug!("{}:  {e}", self.path.display());
+        }
+    }
+}
+
+/// The ways to wait for an archive which is locked by another process.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Locking {
+    /// Fail immediately.
+    NoWait,
+
+    /// Wait at most the given duration.
+    Timeout(Duration),
+
+    /// Wait until the lock is released.
+    #[default]
+    Wait,
+}
+
+#[cfg(unix)]
+fn current(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (file.metadata(), path.metadata()) {
+        (Ok(held), Ok(named)) => {
+            held.dev() == named.dev() && held.ino() == named.ino()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn current(_: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+fn name(path: &Path) -> PathBuf {
+    let mut result = std::ffi::OsString::from(".");
+
+    result.push(path.file_name().unwrap_or_default());
+    result.push(".lock");
+    path.with_file_name(result)
+}
+
+fn wait(
+    file: &File,
+    path: &Path,
+    locking: Locking,
+    start: Instant,
+) -> Result<()> {
+    let timeout = match locking {
+        Locking::NoWait => Duration::ZERO,
+        Locking::Timeout(timeout) => timeout,
+        Locking::Wait => {
+            if FileExt::try_lock(file).is_err() {
+                log::info!("{}:  waiting for another process", path.display());
+                FileExt::lock(file)?;
+            }
+
+            return Ok(());
+        }
+    };
+
+    loop {
+        match FileExt::try_lock(file) {
+            Ok(()) => return Ok(()),
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+            Err(TryLockError::WouldBlock) => {
+                let remaining = timeout.saturating_sub(start.elapsed());
+
+                if remaining.is_zero() {
+                    log::error!(
+                        "{}:  locked by another process",
+                        path.display()
+                    );
+                    return Err(ExitCode::TempFail);
+                }
+
+                std::thread::sleep(remaining.min(Duration::from_millis(50)));
+            }
+        }
+    }
+}
+
+/******************************************************************************/
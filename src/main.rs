@@ -18,9 +18,17 @@
 \******************************************************************************/
 
 use clap::Parser;
+use std::io::Write;
 
 fn main() -> sysexits::ExitCode {
-    aeruginous_tbr::Application::parse().main().into()
+    let application = aeruginous_tbr::Application::parse();
+
+    env_logger::Builder::new()
+        .filter_level(application.verbosity())
+        .format(|f, record| writeln!(f, "{}", record.args()))
+        .init();
+
+    application.main().into()
 }
 
 /******************************************************************************/
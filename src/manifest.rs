@@ -0,0 +1,231 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use sha2::Digest as _;
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+use sysexits::{ExitCode, Result};
+
+/// The supported digest algorithms.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Digest {
+    /// BLAKE3 with the default output length of 256 bits.
+    Blake3,
+
+    /// SHA-256.
+    Sha256,
+}
+
+impl Digest {
+    /// Compute the hexadecimal digest of the given stream.
+    ///
+    /// # Errors
+    ///
+    /// See [`std::io::Error`].
+    pub fn hash<R>(self, mut reader: R) -> io::Result<String>
+    where
+        R: Read,
+    {
+        match self {
+            Self::Blake3 => {
+                let mut hasher = blake3::Hasher::new();
+                io::copy(&mut reader, &mut hasher)?;
+                Ok(hasher.finalize().to_hex().to_string())
+            }
+            Self::Sha256 => {
+                let mut hasher = sha2::Sha256::new();
+                io::copy(&mut reader, &mut hasher)?;
+                Ok(hasher.finalize().iter().fold(String::new(), |mut s, b| {
+                    let _ = write!(s, "{b:02x}");
+                    s
+                }))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Digest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Blake3 => "BLAKE3",
+                Self::Sha256 => "SHA-256",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for Digest {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "b3" | "blake3" => Ok(Self::Blake3),
+            "sha-256" | "sha256" => Ok(Self::Sha256),
+            _ => Err(format!("'{s}' is not supported, yet")),
+        }
+    }
+}
+
+/// A checksum manifest.
+///
+/// A manifest lists the digests of an archive's members.  It is stored in the
+/// same line-based format as the one of `sha256sum` and `b3sum` such that these
+/// utilities can check it, as well.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Manifest {
+    digest: Digest,
+    entries: Vec<(PathBuf, String)>,
+}
+
+impl Manifest {
+    /// The digest algorithm this manifest was created with.
+    #[must_use]
+    pub const fn digest(&self) -> Digest {
+        self.digest
+    }
+
+    /// The members and their hexadecimal digests.
+    #[must_use]
+    pub fn entries(&self) -> &[(PathBuf, String)] {
+        &self.entries
+    }
+
+    /// Create a new and empty instance.
+    #[must_use]
+    pub const fn new(digest: Digest) -> Self {
+        Self {
+            digest,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Append a member and its hexadecimal digest to this manifest.
+    pub fn push<P>(&mut self, path: P, hash: String)
+    where
+        PathBuf: From<P>,
+    {
+        self.entries.push((path.into(), hash));
+    }
+
+    /// Read a manifest from the file system.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn read<P>(path: P, digest: Digest) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut result = Self::new(digest);
+
+        for line in std::fs::read_to_string(path)?.lines() {
+            if line.is_empty() {
+                continue;
+            }
+
+            let (hash, path) = line.split_once(' ').ok_or(ExitCode::DataErr)?;
+            let path = path
+                .strip_prefix(' ')
+                .or_else(|| path.strip_prefix('*'))
+                .ok_or(ExitCode::DataErr)?;
+
+            result.push(path, hash.to_lowercase());
+        }
+
+        Ok(result)
+    }
+
+    /// Check the given manifest against this one.
+    ///
+    /// Each member of this manifest needs to be listed with the same digest by
+    /// the given one.  Each mismatch will be reported.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn verify(&self, actual: &Self) -> Result<()> {
+        let mut result = Ok(());
+
+        for (path, hash) in &self.entries {
+            match actual.entries.iter().find(|(p, _)| p == path) {
+                Some((_, h)) if h == hash => {}
+                Some(_) => {
+                    log::error!("{}:  FAILED", path.display());
+                    result = Err(ExitCode::DataErr);
+                }
+                None => {
+                    log::error!("{}:  missing", path.display());
+                    result = Err(ExitCode::DataErr);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Check the files in the given directory against this manifest.
+    ///
+    /// This is useful to check an archive's extracted files.  Each mismatch
+    /// will be reported.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn verify_directory<P>(&self, directory: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut actual = Self::new(self.digest);
+
+        for (path, _) in &self.entries {
+            if let Ok(file) = File::open(directory.as_ref().join(path)) {
+                actual.push(path, self.digest.hash(file)?);
+            }
+        }
+
+        self.verify(&actual)
+    }
+
+    /// Write this manifest to the file system.
+    ///
+    /// # Errors
+    ///
+    /// See [`sysexits::ExitCode`].
+    pub fn write<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut content = String::new();
+
+        for (member, hash) in &self.entries {
+            let _ = writeln!(content, "{hash}  {}", member.display());
+        }
+
+        Ok(std::fs::write(path, content)?)
+    }
+}
+
+/******************************************************************************/
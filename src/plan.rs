@@ -0,0 +1,165 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// A single change an operation would apply to the file system.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// The file or member would be added.
+    Add(PathBuf),
+
+    /// The file would be deleted.
+    Delete(PathBuf),
+
+    /// The member would be extracted to the given path.
+    Extract(PathBuf),
+
+    /// The member would not be extracted, as the existing file must not be
+    /// replaced.
+    Fail(PathBuf),
+
+    /// The member would be renamed from the first to the second path.
+    Rename(PathBuf, PathBuf),
+
+    /// The existing file or member would be replaced.
+    Replace(PathBuf),
+
+    /// The member would be skipped, keeping the existing file.
+    Skip(PathBuf),
+}
+
+impl Action {
+    /// The path of the affected file or member; the new path of renamed
+    /// members.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Add(p)
+            | Self::Delete(p)
+            | Self::Extract(p)
+            | Self::Fail(p)
+            | Self::Rename(_, p)
+            | Self::Replace(p)
+            | Self::Skip(p) => p,
+        }
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self {
+            Self::Add(_) => "add",
+            Self::Delete(_) => "delete",
+            Self::Extract(_) => "extract",
+            Self::Fail(_) => "fail",
+            Self::Rename(old, new) => {
+                return write!(
+                    f,
+                    "{:<10}{} -> {}",
+                    "rename",
+                    old.display(),
+                    new.display()
+                );
+            }
+            Self::Replace(_) => "replace",
+            Self::Skip(_) => "skip",
+        };
+
+        write!(f, "{action:<10}{}", self.path().display())
+    }
+}
+
+/// The changes an operation would apply to the file system, without applying
+/// them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Plan {
+    actions: Vec<Action>,
+}
+
+impl Plan {
+    /// The actions, in the order in which they would be applied.
+    #[must_use]
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Append the actions of the given plan.
+    pub(crate) fn append(&mut self, mut other: Self) {
+        self.actions.append(&mut other.actions);
+    }
+
+    /// Whether the operation would not change anything.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Append the given action.
+    pub(crate) fn push(&mut self, action: Action) {
+        self.actions.push(action);
+    }
+
+    /// The plan to remove the given archive, including all of its volumes.
+    ///
+    /// # Errors
+    ///
+    /// See [`std::io::Error`].
+    pub(crate) fn remove(path: &Path) -> io::Result<Self> {
+        let paths = crate::volume::paths(path);
+
+        if paths.is_empty() {
+            return Err(io::ErrorKind::NotFound.into());
+        }
+
+        Ok(Self {
+            actions: paths.into_iter().map(Action::Delete).collect(),
+        })
+    }
+
+    /// The plan to write the given file, replacing it if it already exists,
+    /// either as a single file or as a set of volumes.
+    #[must_use]
+    pub(crate) fn write(path: &Path) -> Self {
+        let path = path.to_path_buf();
+
+        Self {
+            actions: vec![if crate::volume::exists(&path) {
+                Action::Replace(path)
+            } else {
+                Action::Add(path)
+            }],
+        }
+    }
+}
+
+impl std::fmt::Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for action in &self.actions {
+            writeln!(f, "{action}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/******************************************************************************/
@@ -0,0 +1,515 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use crate::compression::BrotliWriter;
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+const FOOTER_MAGIC: &[u8; 8] = b"TBRSEEK1";
+const FOOTER_SIZE: usize = 2 + 24 + 1;
+const WINDOW: i32 = 22;
+
+/// The stream header:  a window size of 22 bits, followed by an empty metadata
+/// block for the sake of byte alignment.
+const HEADER: [u8; 2] = [0x6b, 0x00];
+
+/// The last meta-block of the stream:  `ISLAST` and `ISLASTEMPTY`.
+const TRAILER: u8 = 0x03;
+
+/// A member of a seekable archive and the compressed offset of its segment.
+pub struct Entry {
+    pub offset: u64,
+    pub path: PathBuf,
+}
+
+/// An encoder writing a TAR stream in the seekable layout.
+///
+/// Each member of a seekable TAR.BR archive is compressed as an independent
+/// Brotli segment which does not refer to any preceding data.  The segments are
+/// concatenated to a single Brotli stream, followed by metadata blocks holding
+/// an index of the members and their compressed offsets as well as a footer
+/// pointing to this index.  Since Brotli decoders skip metadata blocks, the
+/// archive remains a valid Brotli stream for any other reader.
+pub struct Writer<W>
+where
+    W: Write,
+{
+    block: Vec<u8>,
+    capture: bool,
+    entries: Vec<Entry>,
+    output: Option<Counter<W>>,
+    pending: Option<(tar::EntryType, Vec<u8>)>,
+    quality: i32,
+    remaining: u64,
+    segment: Option<BrotliWriter<Segment<W>>>,
+    start: Option<u64>,
+}
+
+impl<W> Writer<W>
+where
+    W: Write,
+{
+    fn close(&mut self) -> io::Result<()> {
+        if let Some(mut segment) = self.segment.take() {
+            segment.flush()?;
+            self.output = Some(segment.finish()?.finish()?);
+        }
+
+        Ok(())
+    }
+
+    fn data(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.capture {
+            if let Some((_, pending)) = &mut self.pending {
+                pending.extend_from_slice(data);
+            }
+        }
+
+        self.segment.as_mut().map_or(Ok(()), |s| s.write_all(data))
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.close()?;
+
+        let mut output = self.output.take().ok_or_else(broken)?;
+        let mut index = Vec::new();
+
+        for entry in &self.entries {
+            let path = entry.path.to_string_lossy();
+
+            index.extend_from_slice(&entry.offset.to_le_bytes());
+            index.extend_from_slice(&(path.len() as u64).to_le_bytes());
+            index.extend_from_slice(path.as_bytes());
+        }
+
+        let start = output.count;
+
+        for chunk in index.chunks(1 << 24) {
+            output.write_all(&metadata(chunk.len()))?;
+            output.write_all(chunk)?;
+        }
+
+        let mut footer = FOOTER_MAGIC.to_vec();
+        footer.extend_from_slice(&start.to_le_bytes());
+        footer.extend_from_slice(&(index.len() as u64).to_le_bytes());
+
+        output.write_all(&metadata(footer.len()))?;
+        output.write_all(&footer)?;
+        output.write_all(&[TRAILER])?;
+        output.flush()?;
+
+        Ok(output.inner)
+    }
+
+    fn header(&mut self, block: &[u8]) -> io::Result<()> {
+        self.capture = false;
+
+        if block.iter().all(|b| *b == 0) {
+            if self.segment.is_none() {
+                self.open()?;
+            }
+
+            return self.data(block);
+        }
+
+        let header = tar::Header::from_byte_slice(block);
+        let kind = header.entry_type();
+
+        if self.start.is_none() {
+            self.open()?;
+        }
+
+        if kind.is_gnu_longname() || kind.is_pax_local_extensions() {
+            self.capture = true;
+            self.pending = Some((kind, Vec::new()));
+        } else if !kind.is_gnu_longlink() && !kind.is_pax_global_extensions() {
+            let path = match self.pending.take() {
+                Some((kind, data)) => long_path(kind, &data),
+                None => None,
+            };
+
+            self.entries.push(Entry {
+                offset: self.start.take().unwrap_or_default(),
+                path: path.map_or_else(|| header.path().map(Into::into), Ok)?,
+            });
+        }
+
+        self.remaining = header.entry_size()?.div_ceil(512) * 512;
+        self.segment.as_mut().map_or(Ok(()), |s| s.write_all(block))
+    }
+
+    pub fn new(output: W, quality: i32) -> io::Result<Self> {
+        let mut output = Counter {
+            count: 0,
+            inner: output,
+        };
+
+        output.write_all(&HEADER)?;
+
+        Ok(Self {
+            block: Vec::with_capacity(512),
+            capture: false,
+            entries: Vec::new(),
+            output: Some(output),
+            pending: None,
+            quality,
+            remaining: 0,
+            segment: None,
+            start: None,
+        })
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        self.close()?;
+
+        let output = self.output.take().ok_or_else(broken)?;
+
+        self.start = Some(output.count);
+        self.segment = Some(BrotliWriter::catable(
+            Segment {
+                header: Some(Vec::new()),
+                last: None,
+                output,
+            },
+            self.quality,
+            WINDOW,
+        ));
+
+        Ok(())
+    }
+}
+
+impl<W> Write for Writer<W>
+where
+    W: Write,
+{
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, mut buf: &[u8]) -> io::Result<usize> {
+        let length = buf.len();
+
+        while !buf.is_empty() {
+            if self.remaining > 0 {
+                let (data, rest) = buf.split_at(
+                    usize::try_from(self.remaining)
+                        .unwrap_or(usize::MAX)
+                        .min(buf.len()),
+                );
+
+                self.data(data)?;
+                self.remaining -= data.len() as u64;
+                buf = rest;
+            } else {
+                let (data, rest) =
+                    buf.split_at((512 - self.block.len()).min(buf.len()));
+
+                self.block.extend_from_slice(data);
+                buf = rest;
+
+                if self.block.len() == 512 {
+                    let block = std::mem::take(&mut self.block);
+                    self.header(&block)?;
+                    self.block = block;
+                    self.block.clear();
+                }
+            }
+        }
+
+        Ok(length)
+    }
+}
+
+struct Bits<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Bits<'_> {
+    fn read(&mut self, count: usize) -> Option<u64> {
+        let mut result = 0;
+
+        for i in 0..count {
+            let bit = self.bytes.get(self.position / 8)? >> (self.position % 8);
+            result |= u64::from(bit & 1) << i;
+            self.position += 1;
+        }
+
+        Some(result)
+    }
+}
+
+struct Counter<W> {
+    count: u64,
+    inner: W,
+}
+
+impl<W> Write for Counter<W>
+where
+    W: Write,
+{
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let length = self.inner.write(buf)?;
+        self.count += length as u64;
+        Ok(length)
+    }
+}
+
+/// The output of a single segment.
+///
+/// The stream header of the segment will be dropped and its first meta-block
+/// header will be encoded anew such that the segment starts byte-aligned.  The
+/// final empty meta-block will be dropped, as well, such that the next segment
+/// can follow.
+struct Segment<W> {
+    header: Option<Vec<u8>>,
+    last: Option<u8>,
+    output: Counter<W>,
+}
+
+impl<W> Segment<W>
+where
+    W: Write,
+{
+    fn finish(mut self) -> io::Result<Counter<W>> {
+        self.rewrite()?;
+
+        match self.last {
+            Some(TRAILER) => Ok(self.output),
+            _ => Err(io::Error::other("unexpected end of Brotli segment")),
+        }
+    }
+
+    fn hold(&mut self, buf: &[u8]) -> io::Result<()> {
+        if let Some((last, rest)) = buf.split_last() {
+            if let Some(previous) = self.last.replace(*last) {
+                self.output.write_all(&[previous])?;
+            }
+
+            self.output.write_all(rest)?;
+        }
+
+        Ok(())
+    }
+
+    fn rewrite(&mut self) -> io::Result<()> {
+        let Some(header) = self.header.take() else {
+            return Ok(());
+        };
+        let mut bits = Bits {
+            bytes: &header,
+            position: 0,
+        };
+
+        if bits.read(1) == Some(1) && bits.read(3) == Some(0) {
+            bits.read(3);
+        }
+
+        let (Some(0), Some(code @ 0..=2)) = (bits.read(1), bits.read(2)) else {
+            return Err(io::Error::other("unexpected Brotli segment header"));
+        };
+        let nibbles = 4 * (usize::try_from(code).unwrap_or_default() + 4);
+        let (Some(length), Some(1)) = (bits.read(nibbles), bits.read(1)) else {
+            return Err(io::Error::other("unexpected Brotli segment header"));
+        };
+        let start = bits.position.div_ceil(8);
+
+        self.output.write_all(&pack(&[
+            (0, 1),
+            (code, 2),
+            (length, nibbles),
+            (1, 1),
+        ]))?;
+        self.hold(&header[start..])
+    }
+}
+
+impl<W> Write for Segment<W>
+where
+    W: Write,
+{
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.header {
+            Some(header) => {
+                header.extend_from_slice(buf);
+
+                if header.len() >= 8 {
+                    self.rewrite()?;
+                }
+            }
+            None => self.hold(buf)?,
+        }
+
+        Ok(buf.len())
+    }
+}
+
+fn broken() -> io::Error {
+    io::Error::other("the seekable Brotli encoder is in an invalid state")
+}
+
+/// Read the index of the given seekable archive, if any.
+///
+/// # Errors
+///
+/// See [`std::io::Error`].
+pub fn index(file: &mut File) -> io::Result<Option<Vec<Entry>>> {
+    let size = file.seek(SeekFrom::End(0))?;
+    let mut footer = [0; FOOTER_SIZE];
+
+    if size < (HEADER.len() + FOOTER_SIZE) as u64 {
+        return Ok(None);
+    }
+
+    file.seek(SeekFrom::Start(size - FOOTER_SIZE as u64))?;
+    file.read_exact(&mut footer)?;
+
+    if footer[..2] != metadata(24)[..] || &footer[2..10] != FOOTER_MAGIC {
+        return Ok(None);
+    }
+
+    let number =
+        |bytes: &[u8]| u64::from_le_bytes(bytes.try_into().unwrap_or_default());
+    let start = number(&footer[10..18]);
+    let length = usize::try_from(number(&footer[18..26])).unwrap_or_default();
+    let mut index = Vec::with_capacity(length);
+
+    file.seek(SeekFrom::Start(start))?;
+
+    while index.len() < length {
+        let chunk = (length - index.len()).min(1 << 24);
+        let mut header = vec![0; metadata(chunk).len()];
+
+        file.read_exact(&mut header)?;
+
+        if header != metadata(chunk) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "corrupt index of seekable archive",
+            ));
+        }
+
+        file.take(chunk as u64).read_to_end(&mut index)?;
+    }
+
+    let mut result = Vec::new();
+    let mut rest = index.as_slice();
+
+    while !rest.is_empty() {
+        let (Some(offset), Some(length)) = (rest.get(..8), rest.get(8..16))
+        else {
+            break;
+        };
+        let length = usize::try_from(number(length)).unwrap_or_default();
+        let path = rest.get(16..16 + length).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "corrupt index of seekable archive",
+            )
+        })?;
+
+        result.push(Entry {
+            offset: number(offset),
+            path: PathBuf::from(String::from_utf8_lossy(path).to_string()),
+        });
+        rest = &rest[16 + length..];
+    }
+
+    Ok(Some(result))
+}
+
+fn long_path(kind: tar::EntryType, data: &[u8]) -> Option<PathBuf> {
+    if kind.is_gnu_longname() {
+        let name = data.split(|b| *b == 0).next()?;
+        return Some(String::from_utf8_lossy(name).to_string().into());
+    }
+
+    tar::PaxExtensions::new(data)
+        .filter_map(Result::ok)
+        .find(|e| e.key() == Ok("path"))
+        .and_then(|e| e.value().ok().map(PathBuf::from))
+}
+
+/// The header of a metadata meta-block of the given length.
+fn metadata(length: usize) -> Vec<u8> {
+    let bytes: usize = match length {
+        0 => 0,
+        1..=0x100 => 1,
+        0x101..=0x1_0000 => 2,
+        _ => 3,
+    };
+
+    pack(&[
+        (0, 1),
+        (3, 2),
+        (0, 1),
+        (bytes as u64, 2),
+        (length.saturating_sub(1) as u64, 8 * bytes),
+    ])
+}
+
+/// Pack the given bit fields in Brotli's bit order, padded to full bytes.
+fn pack(fields: &[(u64, usize)]) -> Vec<u8> {
+    let mut result = Vec::new();
+    let mut position = 0;
+
+    for (value, count) in fields {
+        for i in 0..*count {
+            if position % 8 == 0 {
+                result.push(0);
+            }
+
+            if let Some(byte) = result.last_mut() {
+                *byte |= u8::from(value >> i & 1 == 1) << (position % 8);
+            }
+
+            position += 1;
+        }
+    }
+
+    result
+}
+
+/// Open a reader decoding the segment at the given offset.
+///
+/// # Errors
+///
+/// See [`std::io::Error`].
+pub fn segment(mut file: File, offset: u64) -> io::Result<impl Read> {
+    file.seek(SeekFrom::Start(offset))?;
+
+    Ok(brotli::Decompressor::new(
+        io::Cursor::new(HEADER).chain(file),
+        4096,
+    ))
+}
+
+/******************************************************************************/
@@ -0,0 +1,325 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use std::{
+    fs::{remove_file, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+use tempfile::NamedTempFile;
+
+/// A reader concatenating all volumes of an archive.
+pub struct Reader {
+    current: File,
+    rest: std::vec::IntoIter<PathBuf>,
+}
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let length = self.current.read(buf)?;
+
+            if length > 0 || buf.is_empty() {
+                return Ok(length);
+            }
+
+            match self.rest.next() {
+                Some(path) => self.current = File::open(path)?,
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+/// A writer splitting its output into volumes of a fixed size.
+///
+/// Without a volume size, the output will be written to a single file.  All
+/// files will be written next to the archive and will only replace it once the
+/// output is persisted.  Dropping the writer without persisting it will discard
+/// the output.
+pub struct Writer {
+    current: NamedTempFile,
+    path: PathBuf,
+    remaining: u64,
+    size: Option<u64>,
+    volumes: Vec<NamedTempFile>,
+}
+
+impl Writer {
+    /// Replace the archive with the written output.
+    ///
+    /// All files will be synchronised to the disk before being renamed into
+    /// place.  Any volumes of the previous archive which are not replaced will
+    /// be removed afterwards.
+    ///
+    /// # Errors
+    ///
+    /// See [`std::io::Error`].
+    pub fn persist(mut self) -> io::Result<()> {
+        let stale = existing(&self.path);
+        let permissions = match stale.first() {
+            Some(path) => Some(path.metadata()?.permissions()),
+            None => None,
+        };
+        let mut written = Vec::new();
+
+        self.volumes.push(self.current);
+
+        for (number, file) in self.volumes.into_iter().enumerate() {
+            let path = if self.size.is_some() {
+                name(&self.path, number + 1)
+            } else {
+                self.path.clone()
+            };
+
+            if let Some(permissions) = &permissions {
+                file.as_file().set_permissions(permissions.clone())?;
+            }
+
+            persist(file, &path)?;
+            written.push(path);
+        }
+
+        for path in stale.iter().filter(|p| !written.contains(p)) {
+            log::info!("removing {}", path.display());
+            remove_file(path)?;
+        }
+
+        synchronise_directory(&self.path)
+    }
+}
+
+impl Write for Writer {
+    fn flush(&mut self) -> io::Result<()> {
+        self.current.flush()
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Some(size) = self.size else {
+            return self.current.write(buf);
+        };
+
+        if self.remaining == 0 {
+            self.current.flush()?;
+            self.volumes.push(std::mem::replace(
+                &mut self.current,
+                temporary(&self.path)?,
+            ));
+            self.remaining = size;
+        }
+
+        let length = usize::try_from(self.remaining)
+            .unwrap_or(usize::MAX)
+            .min(buf.len());
+        let length = self.current.write(&buf[..length])?;
+
+        self.remaining -= length as u64;
+        Ok(length)
+    }
+}
+
+/// Strip the volume number from the given path, if any.
+///
+/// Volumes are numbered by a zero-padded extension of three decimal digits,
+/// such as `archive.tar.br.001`.  The extension will only be considered a
+/// volume number if both the given volume and the first one exist.
+pub fn base(path: &Path) -> PathBuf {
+    let stripped = path.with_extension("");
+    let volume = path
+        .extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .filter(|e| e.len() == 3)
+        .and_then(|e| e.parse().ok())
+        .is_some_and(|n| {
+            n > 0
+                && name(&stripped, n) == path
+                && path.is_file()
+                && name(&stripped, 1).is_file()
+        });
+
+    if volume {
+        stripped
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Create the given archive, either as a single file or as a set of volumes.
+///
+/// The previous archive, if any, will remain untouched until the returned
+/// writer is persisted.  Without a volume size, an existing set of volumes will
+/// be rewritten with the size of its first volume.
+///
+/// # Errors
+///
+/// See [`std::io::Error`].
+pub fn create(path: &Path, size: Option<u64>) -> io::Result<Writer> {
+    let size = match size {
+        None if !path.is_file() && name(path, 1).is_file() => {
+            Some(name(path, 1).metadata()?.len())
+        }
+        size => size,
+    }
+    .map(|s| s.max(1));
+
+    Ok(Writer {
+        current: temporary(path)?,
+        path: path.to_path_buf(),
+        remaining: size.unwrap_or_default(),
+        size,
+        volumes: Vec::new(),
+    })
+}
+
+fn directory(path: &Path) -> &Path {
+    path.parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."))
+}
+
+fn existing(path: &Path) -> Vec<PathBuf> {
+    let mut result = if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        Vec::new()
+    };
+
+    result.extend(
+        (1..=usize::MAX)
+            .map(|n| name(path, n))
+            .take_while(|p| p.is_file()),
+    );
+    result
+}
+
+/// Whether the given archive exists, either as a single file or as a set of
+/// volumes.
+pub fn exists(path: &Path) -> bool {
+    !paths(path).is_empty()
+}
+
+fn name(path: &Path, number: usize) -> PathBuf {
+    let mut result = path.as_os_str().to_owned();
+    result.push(format!(".{number:03}"));
+    result.into()
+}
+
+/// Open the given archive, either as a single file or as a set of volumes.
+///
+/// # Errors
+///
+/// See [`std::io::Error`].
+pub fn open(path: &Path) -> io::Result<Reader> {
+    let mut paths = paths(path).into_iter();
+
+    Ok(Reader {
+        current: File::open(paths.next().as_deref().unwrap_or(path))?,
+        rest: paths,
+    })
+}
+
+/// The files the given archive is stored in.
+///
+/// A single file takes precedence over a set of volumes.
+pub fn paths(path: &Path) -> Vec<PathBuf> {
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+
+    (1..=usize::MAX)
+        .map(|n| name(path, n))
+        .take_while(|p| p.is_file())
+        .collect()
+}
+
+/// Atomically replace the given file with the given temporary file.
+///
+/// The temporary file will take the permissions of the file it replaces, if
+/// any, and will be synchronised to the disk before being renamed into place.
+///
+/// # Errors
+///
+/// See [`std::io::Error`].
+pub fn persist(file: NamedTempFile, path: &Path) -> io::Result<()> {
+    log::info!("writing {}", path.display());
+
+    if let Ok(metadata) = path.metadata() {
+        file.as_file().set_permissions(metadata.permissions())?;
+    }
+
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    synchronise_directory(path)
+}
+
+/// Remove the given archive, including all of its volumes.
+///
+/// # Errors
+///
+/// See [`std::io::Error`].
+pub fn remove(path: &Path) -> io::Result<()> {
+    let paths = paths(path);
+
+    if paths.is_empty() {
+        return remove_file(path);
+    }
+
+    for path in paths {
+        log::info!("removing {}", path.display());
+        remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn synchronise_directory(path: &Path) -> io::Result<()> {
+    File::open(directory(path))?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn synchronise_directory(_: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Create a temporary file next to the given path.
+///
+/// The temporary file will be hidden and removed when dropped.  Like any new
+/// file, it will be readable and writable by everyone, subject to the umask.
+///
+/// # Errors
+///
+/// See [`std::io::Error`].
+pub fn temporary(path: &Path) -> io::Result<NamedTempFile> {
+    let mut builder = tempfile::Builder::new();
+    let mut prefix = std::ffi::OsString::from(".");
+
+    prefix.push(path.file_name().unwrap_or_default());
+    prefix.push(".");
+
+    #[cfg(unix)]
+    builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o666));
+
+    builder
+        .prefix(&prefix)
+        .suffix(".tmp")
+        .tempfile_in(directory(path))
+}
+
+/******************************************************************************/
@@ -18,6 +18,81 @@
 \******************************************************************************/
 
 mod application {
+    mod ar_and_cpio {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::PathBuf;
+
+        #[test]
+        fn ar_conversion() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            std::fs::write(
+                d.to_string() + "/archive.deb",
+                "!<arch>\ndebian-binary/  0           0     0     100644  4         `\n2.0\n",
+            )
+            .unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr convert ".to_string()
+                    + d
+                    + "/archive.deb "
+                    + d
+                    + "/archive.tar.br")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar.br").list().unwrap(),
+                [PathBuf::from("debian-binary")]
+            );
+        }
+
+        #[test]
+        fn ar_unsupported_options() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            std::fs::write(
+                d.to_string() + "/archive.deb",
+                "!<arch>\ndebian-binary/  0           0     0     100644  4         `\n2.0\n",
+            )
+            .unwrap();
+
+            for flags in ["--xattrs", "--dry-run", "--dictionary LICENSE"] {
+                assert_eq!(
+                    Application::parse_from(
+                        ("tbr extract ".to_string()
+                            + flags
+                            + " "
+                            + d
+                            + "/archive.deb -d "
+                            + d)
+                            .split_whitespace()
+                    )
+                    .main(),
+                    Err(sysexits::ExitCode::Usage),
+                    "{flags}"
+                );
+            }
+
+            assert!(!PathBuf::from(d.to_string() + "/debian-binary").exists());
+        }
+
+        #[test]
+        fn cpio_unsupported_mode() {
+            assert_eq!(
+                Application::parse_from(
+                    "tbr update does_not_exist.cpio LICENSE".split_whitespace()
+                )
+                .main(),
+                Err(sysexits::ExitCode::Usage)
+            );
+        }
+    }
+
     mod archive_content_preview {
         use aeruginous_tbr::{Application, Tar};
         use clap::Parser;
@@ -29,31 +104,923 @@ mod application {
                 "tbr list does_not_exist.tar".split_whitespace()
             )
             .main()
-            .is_err());
-        }
-
-        #[test]
-        fn no_extension() {
+            .is_err());
+        }
+
+        #[test]
+        fn no_extension() {
+            assert!(Application::parse_from(
+                "tbr list does_not_exist".split_whitespace()
+            )
+            .main()
+            .is_err());
+        }
+
+        #[test]
+        fn tar_archive() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr list ".to_string() + d + "/archive.tar")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+
+            let tar = Tar::new(d.to_string() + "/archive.tar");
+
+            assert!(tar.exists());
+            assert_eq!(tar.list().unwrap(), [PathBuf::from("LICENSE")]);
+            assert!(tar.remove().is_ok());
+        }
+
+        #[test]
+        fn unsupported_archive_type() {
+            assert!(Application::parse_from(
+                "tbr list does_not_exist.rar".split_whitespace()
+            )
+            .main()
+            .is_err());
+        }
+    }
+    mod archive_comparison {
+        use aeruginous_tbr::Application;
+        use clap::Parser;
+
+        #[test]
+        fn missing_counterpart() {
+            assert!(Application::try_parse_from(
+                "tbr diff does_not_exist.tar".split_whitespace()
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn tar_archives() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/old.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/new.tar Cargo.toml")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr diff ".to_string() + d + "/old.tar " + d + "/new.tar")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr compare --json ".to_string()
+                    + d
+                    + "/old.tar "
+                    + d
+                    + "/new.tar")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+        }
+    }
+
+    mod archive_conversion {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::PathBuf;
+
+        #[test]
+        fn missing_target() {
+            assert!(Application::try_parse_from(
+                "tbr convert does_not_exist.tar".split_whitespace()
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn tar_archive() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr convert ".to_string()
+                    + d
+                    + "/archive.tar "
+                    + d
+                    + "/archive.tar.br")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr list ".to_string() + d + "/archive.tar.br")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar.br").list().unwrap(),
+                [PathBuf::from("LICENSE")]
+            );
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    mod gzip {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::PathBuf;
+
+        #[test]
+        fn conversion() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tar.gz LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr convert ".to_string()
+                    + d
+                    + "/archive.tar.gz "
+                    + d
+                    + "/archive.tbr")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr test ".to_string() + d + "/archive.tar.gz")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tbr").list().unwrap(),
+                [PathBuf::from("LICENSE")]
+            );
+        }
+
+        #[test]
+        fn magic_bytes() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tgz LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+
+            std::fs::rename(
+                d.to_string() + "/archive.tgz",
+                d.to_string() + "/archive",
+            )
+            .unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr list ".to_string() + d + "/archive").split_whitespace()
+            )
+            .main()
+            .is_ok());
+        }
+
+        #[test]
+        fn plain_gzip_file() {
+            assert_eq!(
+                Application::parse_from(
+                    "tbr list does_not_exist.gz".split_whitespace()
+                )
+                .main(),
+                Err(sysexits::ExitCode::Usage)
+            );
+        }
+    }
+
+    #[cfg(feature = "xz")]
+    mod xz {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::PathBuf;
+
+        #[test]
+        fn conversion() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.txz LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr convert ".to_string()
+                    + d
+                    + "/archive.txz "
+                    + d
+                    + "/archive.tar.br")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar.br").list().unwrap(),
+                [PathBuf::from("LICENSE")]
+            );
+        }
+    }
+
+    #[cfg(feature = "zstd")]
+    mod zstd {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::PathBuf;
+
+        #[test]
+        fn long_window() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string()
+                    + d
+                    + "/archive.tar.zst --level -5 --long LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr convert --long=24 ".to_string()
+                    + d
+                    + "/archive.tar.zst "
+                    + d
+                    + "/archive.tzst")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tzst").list().unwrap(),
+                [PathBuf::from("LICENSE")]
+            );
+        }
+    }
+
+    #[cfg(feature = "zip")]
+    mod zip {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::PathBuf;
+
+        #[test]
+        fn conversion() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.zip LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr test ".to_string() + d + "/archive.zip")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr convert ".to_string()
+                    + d
+                    + "/archive.zip "
+                    + d
+                    + "/archive.tar.br")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar.br").list().unwrap(),
+                [PathBuf::from("LICENSE")]
+            );
+        }
+
+        #[test]
+        fn extract_members() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string()
+                    + d
+                    + "/archive.zip Cargo.toml LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr extract ".to_string()
+                    + d
+                    + "/archive.zip LICENSE -d "
+                    + d)
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(PathBuf::from(d.to_string() + "/LICENSE").exists());
+            assert!(!PathBuf::from(d.to_string() + "/Cargo.toml").exists());
+        }
+
+        #[test]
+        fn unsupported_mode() {
+            assert_eq!(
+                Application::parse_from(
+                    "tbr diff does_not_exist.zip .".split_whitespace()
+                )
+                .main(),
+                Err(sysexits::ExitCode::Usage)
+            );
+        }
+
+        #[test]
+        fn unsupported_options() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            for flags in [
+                "--volume-size 10",
+                "--seekable",
+                "--long",
+                "--xattrs",
+                "-m MANIFEST",
+            ] {
+                assert_eq!(
+                    Application::parse_from(
+                        ("tbr create ".to_string()
+                            + flags
+                            + " "
+                            + d
+                            + "/archive.zip LICENSE")
+                            .split_whitespace()
+                    )
+                    .main(),
+                    Err(sysexits::ExitCode::Usage),
+                    "{flags}"
+                );
+            }
+
+            assert!(!PathBuf::from(d.to_string() + "/archive.zip").exists());
+            assert!(Application::parse_from(
+                ("tbr create --level 9 ".to_string()
+                    + d
+                    + "/archive.zip LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr convert --volume-size 4K ".to_string()
+                    + d
+                    + "/archive.zip "
+                    + d
+                    + "/archive.tbr")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+        }
+    }
+
+    mod archive_extraction {
+        use aeruginous_io::PathBufLikeReader;
+        use aeruginous_tbr::Application;
+        use clap::Parser;
+        use std::path::Path;
+
+        #[test]
+        fn permissions_and_mtime() {
+            use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr unpack --touch --umask 077 --numeric-owner ".to_string()
+                    + d
+                    + "/archive.tar -d "
+                    + d)
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+
+            let metadata =
+                std::fs::metadata(d.to_string() + "/LICENSE").unwrap();
+
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+            assert_ne!(
+                metadata.mtime(),
+                std::fs::metadata("LICENSE").unwrap().mtime()
+            );
+        }
+
+        #[test]
+        fn tar_archive() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr unpack ".to_string() + d + "/archive.tar -d " + d)
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                "LICENSE".read_silently().unwrap(),
+                (d.to_string() + "/LICENSE").read_silently().unwrap()
+            );
+        }
+
+        #[test]
+        fn ownership_and_xattrs() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create --xattrs ".to_string()
+                    + d
+                    + "/archive.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+
+            for flags in ["--no-same-owner", "--no-same-owner --same-owner"] {
+                assert!(Application::parse_from(
+                    ("tbr unpack --xattrs ".to_string()
+                        + flags
+                        + " "
+                        + d
+                        + "/archive.tar -d "
+                        + d)
+                        .split_whitespace()
+                )
+                .main()
+                .is_ok());
+            }
+
+            assert_eq!(
+                "LICENSE".read_silently().unwrap(),
+                (d.to_string() + "/LICENSE").read_silently().unwrap()
+            );
+        }
+
+        #[test]
+        fn invalid_umask() {
+            for umask in ["8", "-1", "17777", "rwx"] {
+                assert!(Application::try_parse_from([
+                    "tbr",
+                    "unpack",
+                    "--umask",
+                    umask,
+                    "archive.tar",
+                ])
+                .is_err());
+            }
+        }
+
+        #[test]
+        fn overwrite_policy() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+
+            for (flags, success) in [
+                ("--overwrite fail", true),
+                ("--overwrite replace", true),
+                ("--overwrite skip", true),
+                ("--overwrite keep-newer", true),
+                ("--overwrite rename", true),
+                ("--overwrite fail", false),
+            ] {
+                assert_eq!(
+                    Application::parse_from(
+                        ("tbr unpack ".to_string()
+                            + flags
+                            + " "
+                            + d
+                            + "/archive.tar -d "
+                            + d)
+                            .split_whitespace()
+                    )
+                    .main()
+                    .is_ok(),
+                    success
+                );
+            }
+
+            assert!(Path::new(&(d.to_string() + "/LICENSE.~1~")).exists());
+        }
+
+        #[test]
+        fn seekable_members() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create --seekable ".to_string()
+                    + d
+                    + "/archive.tbr Cargo.toml LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr unpack ".to_string()
+                    + d
+                    + "/archive.tbr LICENSE -d "
+                    + d)
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr cat ".to_string() + d + "/archive.tbr Cargo.toml")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::try_parse_from(
+                ("tbr cat ".to_string() + d + "/archive.tbr")
+                    .split_whitespace()
+            )
+            .is_err());
+            assert_eq!(
+                "LICENSE".read_silently().unwrap(),
+                (d.to_string() + "/LICENSE").read_silently().unwrap()
+            );
+            assert!(!std::path::Path::new(&(d.to_string() + "/Cargo.toml"))
+                .exists());
+        }
+    }
+
+    mod archive_integrity_test {
+        use aeruginous_tbr::Application;
+        use clap::Parser;
+
+        #[test]
+        fn corrupt_tar_archive() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            std::fs::write(d.to_string() + "/archive.tar", [1; 512]).unwrap();
+
+            assert_eq!(
+                Application::parse_from(
+                    ("tbr verify ".to_string() + d + "/archive.tar")
+                        .split_whitespace()
+                )
+                .main(),
+                Err(sysexits::ExitCode::DataErr)
+            );
+        }
+
+        #[test]
+        fn tar_archive() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr test ".to_string() + d + "/archive.tar")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+        }
+    }
+
+    mod archive_merge {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::PathBuf;
+        use sysexits::ExitCode;
+
+        #[test]
+        fn missing_sources() {
+            assert!(Application::try_parse_from(
+                "tbr merge does_not_exist.tbr".split_whitespace()
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn tar_and_ar_archives() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            std::fs::write(
+                d.to_string() + "/archive.deb",
+                "!<arch>\ndebian-binary/  0           0     0     100644  4         `\n2.0\n",
+            )
+            .unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Application::parse_from(
+                    ("tbr merge ".to_string()
+                        + d
+                        + "/merged.tbr "
+                        + d
+                        + "/archive.tar "
+                        + d
+                        + "/archive.tar")
+                        .split_whitespace()
+                )
+                .main(),
+                Err(ExitCode::DataErr)
+            );
+            assert!(Application::parse_from(
+                ("tbr merge --conflict last ".to_string()
+                    + d
+                    + "/merged.tbr "
+                    + d
+                    + "/archive.tar "
+                    + d
+                    + "/archive.deb "
+                    + d
+                    + "/archive.tar")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Tar::new(d.to_string() + "/merged.tbr").list().unwrap(),
+                [PathBuf::from("debian-binary"), PathBuf::from("LICENSE")]
+            );
+        }
+    }
+
+    mod archive_member_renaming {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::PathBuf;
+        use sysexits::ExitCode;
+
+        #[test]
+        fn collision() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string()
+                    + d
+                    + "/archive.tar Cargo.toml LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Application::parse_from(
+                    ("tbr rename ".to_string()
+                        + d
+                        + "/archive.tar LICENSE Cargo.toml")
+                        .split_whitespace()
+                )
+                .main(),
+                Err(ExitCode::DataErr)
+            );
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar").list().unwrap(),
+                [PathBuf::from("Cargo.toml"), PathBuf::from("LICENSE")]
+            );
+        }
+
+        #[test]
+        fn odd_number_of_paths() {
+            assert_eq!(
+                Application::parse_from(
+                    "tbr rename does_not_exist.tar LICENSE".split_whitespace()
+                )
+                .main(),
+                Err(ExitCode::Usage)
+            );
+        }
+
+        #[test]
+        fn tar_archive() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr mv ".to_string() + d + "/archive.tar LICENSE COPYING")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar").list().unwrap(),
+                [PathBuf::from("COPYING")]
+            );
+        }
+    }
+
+    mod checksum_manifest {
+        use aeruginous_tbr::Application;
+        use clap::Parser;
+
+        #[test]
+        fn tar_archive() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr create ".to_string()
+                    + d
+                    + "/archive.tar LICENSE --digest b3 -m "
+                    + d
+                    + "/B3SUMS")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr verify ".to_string()
+                    + d
+                    + "/archive.tar --digest b3 -m "
+                    + d
+                    + "/B3SUMS")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr verify ".to_string()
+                    + d
+                    + "/archive.tar --digest b3 -d . -m "
+                    + d
+                    + "/B3SUMS")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert_eq!(
+                Application::parse_from(
+                    ("tbr verify ".to_string()
+                        + d
+                        + "/archive.tar -d . -m "
+                        + d
+                        + "/B3SUMS")
+                        .split_whitespace()
+                )
+                .main(),
+                Err(sysexits::ExitCode::DataErr)
+            );
+        }
+    }
+
+    mod archive_removal {
+        use aeruginous_tbr::Application;
+        use clap::Parser;
+
+        #[test]
+        fn file_does_not_exist() {
+            assert!(Application::parse_from(
+                "tbr remove does_not_exist.tar".split_whitespace()
+            )
+            .main()
+            .is_err());
+        }
+    }
+
+    mod dictionary_training {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::PathBuf;
+
+        #[test]
+        fn life_cycle() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Application::parse_from(
+                ("tbr train-dictionary --dictionary-size 4096 ".to_string()
+                    + d
+                    + "/dictionary LICENSE Cargo.toml")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr create --dictionary ".to_string()
+                    + d
+                    + "/dictionary "
+                    + d
+                    + "/archive.tar.br LICENSE")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
             assert!(Application::parse_from(
-                "tbr list does_not_exist".split_whitespace()
+                ("tbr test --dictionary ".to_string()
+                    + d
+                    + "/dictionary "
+                    + d
+                    + "/archive.tar.br")
+                    .split_whitespace()
             )
             .main()
-            .is_err());
+            .is_ok());
+            assert!(Tar::new(d.to_string() + "/archive.tar.br")
+                .test()
+                .is_err());
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar.br")
+                    .with_dictionary(
+                        aeruginous_tbr::Dictionary::read(
+                            d.to_string() + "/dictionary"
+                        )
+                        .unwrap()
+                    )
+                    .list()
+                    .unwrap(),
+                [PathBuf::from("LICENSE")]
+            );
         }
+    }
+
+    mod archive_update {
+        use aeruginous_tbr::{Application, Tar};
+        use clap::Parser;
+        use std::path::{Path, PathBuf};
+        use sysexits::ExitCode;
 
         #[test]
-        fn tar_archive() {
+        fn tar_archive_creation() {
             let d = tempfile::tempdir().unwrap();
             let d = d.path().to_str().unwrap();
 
             assert!(Application::parse_from(
-                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
-                    .split_whitespace()
-            )
-            .main()
-            .is_ok());
-            assert!(Application::parse_from(
-                ("tbr list ".to_string() + d + "/archive.tar")
+                ("tbr create ".to_string() + d + "/archive.tar Cargo.*")
                     .split_whitespace()
             )
             .main()
@@ -62,23 +1029,168 @@ mod application {
             let tar = Tar::new(d.to_string() + "/archive.tar");
 
             assert!(tar.exists());
-            assert_eq!(tar.list().unwrap(), [PathBuf::from("LICENSE")]);
+            assert_eq!(
+                tar.list().unwrap(),
+                [PathBuf::from("Cargo.lock"), PathBuf::from("Cargo.toml")]
+            );
             assert!(tar.remove().is_ok());
         }
 
         #[test]
-        fn unsupported_archive_type() {
+        fn dry_run() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+            let run = |command: &str| {
+                Application::parse_from(
+                    ("tbr ".to_string() + command).split_whitespace(),
+                )
+                .main()
+            };
+
+            assert!(run(&("create -n ".to_string()
+                + d
+                + "/archive.tar LICENSE"))
+            .is_ok());
+            assert!(!Tar::new(d.to_string() + "/archive.tar").exists());
+            assert!(run(&("create ".to_string() + d + "/archive.tar LICENSE"))
+                .is_ok());
+
+            for command in [
+                "update --dry-run ",
+                "remove -n ",
+                "unpack -n -d ",
+                "rename -n ",
+            ] {
+                let arguments = if command.starts_with("unpack") {
+                    d.to_string() + " " + d + "/archive.tar"
+                } else if command.starts_with("remove") {
+                    d.to_string() + "/archive.tar"
+                } else if command.starts_with("rename") {
+                    d.to_string() + "/archive.tar LICENSE COPYING"
+                } else {
+                    d.to_string() + "/archive.tar Cargo.toml LICENSE"
+                };
+
+                assert!(run(&(command.to_string() + &arguments)).is_ok());
+            }
+
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar").list().unwrap(),
+                [PathBuf::from("LICENSE")]
+            );
+            assert!(!Path::new(&(d.to_string() + "/LICENSE")).exists());
+            assert!(run(&("create ".to_string()
+                + d
+                + "/archive.tar Cargo.toml LICENSE"))
+            .is_ok());
+            std::fs::write(d.to_string() + "/Cargo.toml", "").unwrap();
+            std::fs::write(d.to_string() + "/LICENSE", "").unwrap();
+            assert_eq!(
+                run(&("unpack -n --overwrite fail -d ".to_string()
+                    + d
+                    + " "
+                    + d
+                    + "/archive.tar")),
+                Err(ExitCode::CantCreat)
+            );
+            assert!(std::fs::read_to_string(d.to_string() + "/LICENSE")
+                .unwrap()
+                .is_empty());
+            std::fs::remove_file(d.to_string() + "/Cargo.toml").unwrap();
+            std::fs::remove_file(d.to_string() + "/LICENSE").unwrap();
+            assert_eq!(
+                run(&("extract -n ".to_string() + d + "/archive.cpio")),
+                Err(ExitCode::Usage)
+            );
+            assert_eq!(
+                run(&("rename -n ".to_string()
+                    + d
+                    + "/archive.tar does_not_exist new")),
+                Err(ExitCode::DataErr)
+            );
+            assert!(run(&("create ".to_string() + d + "/other.tar LICENSE"))
+                .is_ok());
+            assert_eq!(
+                run(&("merge -n ".to_string()
+                    + d
+                    + "/merged.tar "
+                    + d
+                    + "/archive.tar "
+                    + d
+                    + "/other.tar")),
+                Err(ExitCode::DataErr)
+            );
+            assert!(run(&("merge -n --conflict last ".to_string()
+                + d
+                + "/merged.tar "
+                + d
+                + "/archive.tar "
+                + d
+                + "/other.tar"))
+            .is_ok());
+            assert!(!Path::new(&(d.to_string() + "/merged.tar")).exists());
+        }
+
+        #[test]
+        fn locked_archive() {
+            let d = tempfile::tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+            let lock =
+                std::fs::File::create(d.to_string() + "/.archive.tar.lock")
+                    .unwrap();
+
+            assert!(fs4::FileExt::try_lock(&lock).is_ok());
+
+            for flags in ["--no-wait", "--wait=0", "--wait --no-wait"] {
+                assert_eq!(
+                    Application::parse_from(
+                        ("tbr create ".to_string()
+                            + flags
+                            + " "
+                            + d
+                            + "/archive.tar LICENSE")
+                            .split_whitespace()
+                    )
+                    .main(),
+                    Err(ExitCode::TempFail)
+                );
+            }
+
+            drop(lock);
             assert!(Application::parse_from(
-                "tbr list does_not_exist.zip".split_whitespace()
+                ("tbr create --no-wait ".to_string()
+                    + d
+                    + "/archive.tar LICENSE")
+                    .split_whitespace()
             )
             .main()
-            .is_err());
+            .is_ok());
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar").list().unwrap(),
+                [PathBuf::from("LICENSE")]
+            );
         }
     }
-    mod archive_extraction {
+
+    mod multi_volume {
         use aeruginous_io::PathBufLikeReader;
         use aeruginous_tbr::Application;
         use clap::Parser;
+        use std::path::Path;
+
+        #[test]
+        fn invalid_volume_size() {
+            for size in ["0", "1X", "G", "99999999999T"] {
+                assert!(Application::try_parse_from([
+                    "tbr",
+                    "create",
+                    "--volume-size",
+                    size,
+                    "archive.tbr",
+                ])
+                .is_err());
+            }
+        }
 
         #[test]
         fn tar_archive() {
@@ -86,13 +1198,22 @@ mod application {
             let d = d.path().to_str().unwrap();
 
             assert!(Application::parse_from(
-                ("tbr create ".to_string() + d + "/archive.tar LICENSE")
+                ("tbr create --volume-size 4K ".to_string()
+                    + d
+                    + "/archive.tbr Cargo.toml LICENSE")
                     .split_whitespace()
             )
             .main()
             .is_ok());
+            assert!(Path::new(&(d.to_string() + "/archive.tbr.002")).exists());
             assert!(Application::parse_from(
-                ("tbr unpack ".to_string() + d + "/archive.tar -d " + d)
+                ("tbr list ".to_string() + d + "/archive.tbr.001")
+                    .split_whitespace()
+            )
+            .main()
+            .is_ok());
+            assert!(Application::parse_from(
+                ("tbr unpack ".to_string() + d + "/archive.tbr.001 -d " + d)
                     .split_whitespace()
             )
             .main()
@@ -104,45 +1225,217 @@ mod application {
         }
     }
 
-    mod archive_removal {
-        use aeruginous_tbr::Application;
-        use clap::Parser;
+    mod shell_integration {
+        use aeruginous_tbr::{Application, ApplicationMode};
+        use clap::{CommandFactory, Parser};
+        use clap_complete::Shell;
 
         #[test]
-        fn file_does_not_exist() {
-            assert!(Application::parse_from(
-                "tbr remove does_not_exist.tar".split_whitespace()
-            )
+        fn completions() {
+            let command = Application::command();
+            let names = command
+                .get_subcommands()
+                .flat_map(|c| {
+                    std::iter::once(c.get_name()).chain(c.get_visible_aliases())
+                })
+                .collect::<Vec<_>>();
+
+            for name in &names {
+                assert_eq!(
+                    name.parse::<ApplicationMode>().is_ok(),
+                    !["completions", "man", "manual"].contains(name),
+                    "{name}"
+                );
+            }
+
+            for shell in [Shell::Bash, Shell::Zsh] {
+                let mut output = Vec::new();
+
+                clap_complete::generate(
+                    shell,
+                    &mut Application::command(),
+                    "tbr",
+                    &mut output,
+                );
+
+                let output = String::from_utf8(output).unwrap();
+
+                for name in &names {
+                    assert!(output.contains(name), "{shell}:  {name}");
+                }
+            }
+
+            for shell in ["bash", "elvish", "fish", "powershell", "zsh"] {
+                assert!(Application::try_parse_from([
+                    "tbr",
+                    "completions",
+                    shell
+                ])
+                .is_ok());
+            }
+
+            assert!(Application::try_parse_from(["tbr", "completions", "sh"])
+                .is_err());
+            assert!(
+                Application::try_parse_from(["tbr", "completions"]).is_err()
+            );
+        }
+
+        #[test]
+        fn files_named_like_commands() {
+            assert!(Application::parse_from([
+                "tbr",
+                "extract",
+                "does_not_exist.tar",
+                "completions"
+            ])
             .main()
             .is_err());
         }
-    }
-
-    mod archive_update {
-        use aeruginous_tbr::{Application, Tar};
-        use clap::Parser;
-        use std::path::PathBuf;
 
         #[test]
-        fn tar_archive_creation() {
+        fn manual() {
             let d = tempfile::tempdir().unwrap();
             let d = d.path().to_str().unwrap();
 
-            assert!(Application::parse_from(
-                ("tbr create ".to_string() + d + "/archive.tar Cargo.*")
-                    .split_whitespace()
+            assert!(Application::parse_from(["tbr", "manual", d])
+                .main()
+                .is_ok());
+
+            let output =
+                std::fs::read_to_string(d.to_string() + "/tbr.1").unwrap();
+
+            assert!(output.contains(".TH tbr 1"));
+            assert!(output.contains("tbr\\-extract(1)"));
+            assert!(output.contains("aliases: decompress, uncompress, unpack"));
+            assert!(output.contains("completions"));
+
+            for (command, alias, option) in [
+                ("create", "compress", "\\-\\-volume\\-size"),
+                ("extract", "unpack", "\\-\\-overwrite"),
+                ("list", "show", "\\-\\-manifest"),
+                ("merge", "concat", "\\-\\-conflict"),
+            ] {
+                let page = std::fs::read_to_string(
+                    d.to_string() + "/tbr-" + command + ".1",
+                )
+                .unwrap();
+
+                assert!(page.contains(alias), "{command}:  {alias}");
+                assert!(page.contains(option), "{command}:  {option}");
+            }
+
+            assert!(Application::try_parse_from(["tbr", "manual"]).is_ok());
+            assert!(Application::try_parse_from(["tbr", "man"]).is_ok());
+        }
+    }
+
+    mod subcommands {
+        use aeruginous_tbr::{Application, ApplicationMode};
+        use clap::{CommandFactory, Parser};
+
+        #[test]
+        fn aliases() {
+            let command = Application::command();
+
+            for names in [
+                &["create", "add", "compress", "edit", "update"][..],
+                &["list", "content", "info", "show"],
+                &["extract", "decompress", "uncompress", "unpack"],
+                &["delete", "remove"],
+                &["test", "check", "verify"],
+                &["cat", "print"],
+                &["diff", "compare"],
+                &["convert", "recompress", "repack"],
+                &["merge", "combine", "concat"],
+                &["rename", "move", "mv"],
+                &["train", "train-dictionary"],
+            ] {
+                for name in names {
+                    assert_eq!(
+                        command.find_subcommand(name).unwrap().get_name(),
+                        names[0]
+                    );
+                    assert_eq!(
+                        name.parse::<ApplicationMode>(),
+                        names[0].parse::<ApplicationMode>()
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn foreign_options() {
+            for arguments in [
+                "tbr list -d . archive.tar",
+                "tbr show --level 9 archive.tar",
+                "tbr create --overwrite skip archive.tar LICENSE",
+                "tbr extract --level 9 archive.tar",
+                "tbr delete --dictionary dictionary archive.tar",
+                "tbr cat --json archive.tar LICENSE",
+                "tbr diff --conflict last archive.tar other.tar",
+            ] {
+                assert!(
+                    Application::try_parse_from(arguments.split_whitespace())
+                        .is_err(),
+                    "{arguments}"
+                );
+            }
+
+            assert!(Application::try_parse_from(
+                "tbr extract -d . archive.tar".split_whitespace()
             )
-            .main()
             .is_ok());
+        }
 
-            let tar = Tar::new(d.to_string() + "/archive.tar");
+        #[test]
+        fn unexpected_files() {
+            for arguments in [
+                "tbr list archive.tar LICENSE",
+                "tbr delete archive.tar LICENSE",
+                "tbr test archive.tar LICENSE",
+                "tbr cat archive.tar LICENSE COPYING",
+                "tbr diff archive.tar other.tar third.tar",
+                "tbr convert archive.tar archive.tbr archive.tgz",
+            ] {
+                assert!(
+                    Application::try_parse_from(arguments.split_whitespace())
+                        .is_err(),
+                    "{arguments}"
+                );
+            }
+        }
+    }
 
-            assert!(tar.exists());
-            assert_eq!(
-                tar.list().unwrap(),
-                [PathBuf::from("Cargo.lock"), PathBuf::from("Cargo.toml")]
-            );
-            assert!(tar.remove().is_ok());
+    mod verbosity {
+        use aeruginous_tbr::Application;
+        use clap::Parser;
+        use log::LevelFilter;
+
+        #[test]
+        fn flags() {
+            for (flags, level) in [
+                ("", LevelFilter::Warn),
+                ("-v", LevelFilter::Info),
+                ("-vv", LevelFilter::Debug),
+                ("-v -v -v", LevelFilter::Trace),
+                ("--quiet", LevelFilter::Error),
+                ("-qq", LevelFilter::Off),
+            ] {
+                assert_eq!(
+                    Application::parse_from(
+                        ("tbr list archive.tar ".to_string() + flags)
+                            .split_whitespace()
+                    )
+                    .verbosity(),
+                    level
+                );
+            }
+
+            assert!(Application::try_parse_from(
+                "tbr list archive.tar -v -q".split_whitespace()
+            )
+            .is_err());
         }
     }
 }
@@ -159,6 +1452,22 @@ mod application_mode {
             );
         }
 
+        #[test]
+        fn conversion() {
+            assert_eq!(
+                ApplicationMode::Conversion.clone(),
+                ApplicationMode::Conversion
+            );
+        }
+
+        #[test]
+        fn difference() {
+            assert_eq!(
+                ApplicationMode::Difference.clone(),
+                ApplicationMode::Difference
+            );
+        }
+
         #[test]
         fn extraction() {
             assert_eq!(
@@ -167,6 +1476,19 @@ mod application_mode {
             );
         }
 
+        #[test]
+        fn merge() {
+            assert_eq!(ApplicationMode::Merge.clone(), ApplicationMode::Merge);
+        }
+
+        #[test]
+        fn output() {
+            assert_eq!(
+                ApplicationMode::Output.clone(),
+                ApplicationMode::Output
+            );
+        }
+
         #[test]
         fn removal() {
             assert_eq!(
@@ -175,6 +1497,27 @@ mod application_mode {
             );
         }
 
+        #[test]
+        fn renaming() {
+            assert_eq!(
+                ApplicationMode::Renaming.clone(),
+                ApplicationMode::Renaming
+            );
+        }
+
+        #[test]
+        fn test() {
+            assert_eq!(ApplicationMode::Test.clone(), ApplicationMode::Test);
+        }
+
+        #[test]
+        fn training() {
+            assert_eq!(
+                ApplicationMode::Training.clone(),
+                ApplicationMode::Training
+            );
+        }
+
         #[test]
         fn update() {
             assert_eq!(
@@ -192,6 +1535,22 @@ mod application_mode {
             assert_eq!(format!("{:?}", ApplicationMode::Content), "Content");
         }
 
+        #[test]
+        fn conversion() {
+            assert_eq!(
+                format!("{:?}", ApplicationMode::Conversion),
+                "Conversion"
+            );
+        }
+
+        #[test]
+        fn difference() {
+            assert_eq!(
+                format!("{:?}", ApplicationMode::Difference),
+                "Difference"
+            );
+        }
+
         #[test]
         fn extraction() {
             assert_eq!(
@@ -200,11 +1559,36 @@ mod application_mode {
             );
         }
 
+        #[test]
+        fn merge() {
+            assert_eq!(format!("{:?}", ApplicationMode::Merge), "Merge");
+        }
+
+        #[test]
+        fn output() {
+            assert_eq!(format!("{:?}", ApplicationMode::Output), "Output");
+        }
+
         #[test]
         fn removal() {
             assert_eq!(format!("{:?}", ApplicationMode::Removal), "Removal");
         }
 
+        #[test]
+        fn renaming() {
+            assert_eq!(format!("{:?}", ApplicationMode::Renaming), "Renaming");
+        }
+
+        #[test]
+        fn test() {
+            assert_eq!(format!("{:?}", ApplicationMode::Test), "Test");
+        }
+
+        #[test]
+        fn training() {
+            assert_eq!(format!("{:?}", ApplicationMode::Training), "Training");
+        }
+
         #[test]
         fn update() {
             assert_eq!(format!("{:?}", ApplicationMode::Update), "Update");
@@ -222,6 +1606,22 @@ mod application_mode {
             );
         }
 
+        #[test]
+        fn conversion() {
+            assert_eq!(
+                format!("{}", ApplicationMode::Conversion),
+                "archive conversion"
+            );
+        }
+
+        #[test]
+        fn difference() {
+            assert_eq!(
+                format!("{}", ApplicationMode::Difference),
+                "archive comparison"
+            );
+        }
+
         #[test]
         fn extraction() {
             assert_eq!(
@@ -230,6 +1630,19 @@ mod application_mode {
             );
         }
 
+        #[test]
+        fn merge() {
+            assert_eq!(format!("{}", ApplicationMode::Merge), "archive merge");
+        }
+
+        #[test]
+        fn output() {
+            assert_eq!(
+                format!("{}", ApplicationMode::Output),
+                "archive member output"
+            );
+        }
+
         #[test]
         fn removal() {
             assert_eq!(
@@ -238,6 +1651,30 @@ mod application_mode {
             );
         }
 
+        #[test]
+        fn renaming() {
+            assert_eq!(
+                format!("{}", ApplicationMode::Renaming),
+                "archive member renaming"
+            );
+        }
+
+        #[test]
+        fn test() {
+            assert_eq!(
+                format!("{}", ApplicationMode::Test),
+                "archive integrity test"
+            );
+        }
+
+        #[test]
+        fn training() {
+            assert_eq!(
+                format!("{}", ApplicationMode::Training),
+                "dictionary training"
+            );
+        }
+
         #[test]
         fn update() {
             assert_eq!(
@@ -275,14 +1712,34 @@ mod application_mode {
                 list,
                 show
             },
+            Conversion {
+                convert,
+                recompress,
+                repack
+            },
+            Difference { compare, diff },
             Extraction {
                 decompress,
                 extract,
                 uncompress,
                 unpack
             },
+            Merge {
+                combine,
+                concat,
+                merge
+            },
+            Output { cat, print },
             Removal { delete, remove },
+            Renaming { mv, rename },
+            Test {
+                check,
+                test,
+                verify
+            },
+            Training { train },
             Update {
+                add,
                 compress,
                 create,
                 edit,
@@ -290,12 +1747,32 @@ mod application_mode {
             }
         );
 
+        #[test]
+        fn r#move() {
+            assert_eq!(
+                ApplicationMode::from_str("move").unwrap(),
+                ApplicationMode::Renaming
+            );
+        }
+
+        #[test]
+        fn train_dictionary() {
+            assert_eq!(
+                ApplicationMode::from_str("train-dictionary").unwrap(),
+                ApplicationMode::Training
+            );
+        }
+
         #[test]
         fn failure() {
             assert_eq!(
                 ApplicationMode::from_str("").unwrap_err(),
                 "'' is not supported, yet"
             );
+            assert_eq!(
+                ApplicationMode::from_str("man").unwrap_err(),
+                "'man' is not supported, yet"
+            );
         }
     }
 }
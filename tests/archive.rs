@@ -17,71 +17,1636 @@
 |                                                                              |
 \******************************************************************************/
 
+mod ar {
+    use aeruginous_io::PathBufLikeReader;
+    use aeruginous_tbr::{Ar, ExtractOptions, Overwrite, Tar};
+    use std::path::PathBuf;
+    use sysexits::ExitCode;
+    use tempfile::tempdir;
+
+    fn member(name: &str, mode: u32, data: &[u8]) -> Vec<u8> {
+        let mut result = format!(
+            "{name:<16}{:<12}{:<6}{:<6}{mode:<8o}{:<10}`\n",
+            0,
+            0,
+            0,
+            data.len()
+        )
+        .into_bytes();
+
+        result.extend_from_slice(data);
+
+        if data.len() % 2 == 1 {
+            result.push(b'\n');
+        }
+
+        result
+    }
+
+    fn fixture(path: &str) {
+        let mut bytes = b"!<arch>\n".to_vec();
+        let names = b"a_very_long_member_name.txt/\n";
+
+        bytes.extend(member("//", 0o100_644, names));
+        bytes.extend(member("debian-binary/", 0o100_644, b"2.0\n"));
+        bytes.extend(member("/0", 0o100_644, b"odd"));
+        bytes.extend(member("#1/9", 0o100_644, b"bsd_styleabc"));
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn convert() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let ar = Ar::new(d.to_string() + "/archive.a");
+        let tbr = Tar::new(d.to_string() + "/archive.tbr");
+
+        fixture(&(d.to_string() + "/archive.a"));
+
+        assert!(ar.convert(&tbr).is_ok());
+        assert!(tbr.test().is_ok());
+        assert_eq!(ar.list().unwrap(), tbr.list().unwrap());
+    }
+
+    #[test]
+    fn life_cycle() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let ar = Ar::new(d.to_string() + "/archive.deb");
+
+        assert!(!ar.exists());
+        fixture(&(d.to_string() + "/archive.deb"));
+        assert!(ar.exists());
+        assert_eq!(
+            ar.list().unwrap(),
+            [
+                PathBuf::from("debian-binary"),
+                PathBuf::from("a_very_long_member_name.txt"),
+                PathBuf::from("bsd_style")
+            ]
+        );
+        assert!(ar.extract(d).is_ok());
+        assert_eq!(
+            (d.to_string() + "/a_very_long_member_name.txt")
+                .read_silently()
+                .unwrap(),
+            "odd"
+        );
+        assert_eq!(
+            (d.to_string() + "/bsd_style").read_silently().unwrap(),
+            "abc"
+        );
+        assert!(ar.remove().is_ok());
+        assert!(!ar.exists());
+    }
+
+    #[test]
+    fn extract_options() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let ar = Ar::new(d.to_string() + "/archive.a").with_extract_options(
+            ExtractOptions::default()
+                .with_mask(0o077)
+                .with_overwrite(Overwrite::Fail),
+        );
+
+        fixture(&(d.to_string() + "/archive.a"));
+
+        assert!(ar.extract(d).is_ok());
+        assert_eq!(
+            std::fs::metadata(d.to_string() + "/debian-binary")
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o7777,
+            0o600
+        );
+        assert_eq!(ar.extract(d), Err(ExitCode::CantCreat));
+    }
+
+    #[test]
+    fn extract_members() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let ar = Ar::new(d.to_string() + "/archive.a");
+
+        fixture(&(d.to_string() + "/archive.a"));
+
+        assert!(ar.extract_members(d, &["bsd_style"]).is_ok());
+        assert!(PathBuf::from(d.to_string() + "/bsd_style").exists());
+        assert!(!PathBuf::from(d.to_string() + "/debian-binary").exists());
+        assert_eq!(
+            ar.extract_members(d, &["does_not_exist.txt"]),
+            Err(ExitCode::DataErr)
+        );
+    }
+
+    #[test]
+    fn not_an_ar_archive() {
+        assert_eq!(Ar::new("LICENSE").list(), Err(ExitCode::DataErr));
+    }
+
+    #[test]
+    fn symlink_escape() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mut bytes = b"!<arch>\n".to_vec();
+
+        std::fs::create_dir(d.to_string() + "/outside").unwrap();
+        bytes.extend(member(
+            "link/",
+            0o120_777,
+            (d.to_string() + "/outside").as_bytes(),
+        ));
+        bytes.extend(member("#1/10", 0o100_644, b"link/pwnedodd"));
+        std::fs::write(d.to_string() + "/archive.a", bytes).unwrap();
+
+        assert_eq!(
+            Ar::new(d.to_string() + "/archive.a")
+                .extract(d.to_string() + "/output"),
+            Err(ExitCode::DataErr)
+        );
+        assert!(
+            !std::path::Path::new(&(d.to_string() + "/outside/pwned")).exists()
+        );
+    }
+}
+
 mod brotli {
     use aeruginous_io::PathBufLikeReader;
-    use aeruginous_tbr::Brotli;
+    use aeruginous_tbr::{
+        Action, Brotli, Dictionary, ExtractOptions, Overwrite, Tar,
+    };
+    use sysexits::ExitCode;
+    use tempfile::tempdir;
+
+    #[test]
+    fn atomic_compression() {
+        let d = tempdir().unwrap();
+        let br = Brotli::new(d.path().join("archive.br"));
+
+        assert!(br.compress("LICENSE").is_ok());
+        assert!(br.compress("does_not_exist.txt").is_err());
+        assert!(br.decompress(d.path()).is_ok());
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            d.path().join("archive").read_silently().unwrap()
+        );
+        assert_eq!(std::fs::read_dir(d.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn dry_run() {
+        let d = tempdir().unwrap();
+        let br = Brotli::new(d.path().join("archive.br"));
+
+        assert_eq!(
+            br.plan_compress("LICENSE").unwrap().actions(),
+            [Action::Add(d.path().join("archive.br"))]
+        );
+        assert!(br.plan_compress("does_not_exist.txt").is_err());
+        assert!(br.plan_decompress(d.path()).is_err());
+        assert!(br.plan_remove().is_err());
+        assert_eq!(std::fs::read_dir(d.path()).unwrap().count(), 0);
+
+        assert!(br.compress("LICENSE").is_ok());
+        assert_eq!(
+            br.plan_compress("LICENSE").unwrap().actions(),
+            [Action::Replace(d.path().join("archive.br"))]
+        );
+        assert_eq!(
+            br.plan_decompress(d.path()).unwrap().actions(),
+            [Action::Extract(d.path().join("archive"))]
+        );
+        assert_eq!(
+            br.plan_remove().unwrap().actions(),
+            [Action::Delete(d.path().join("archive.br"))]
+        );
+        assert!(!d.path().join("archive").exists());
+        assert!(br.decompress(d.path()).is_ok());
+        assert_eq!(
+            Brotli::new(d.path().join("archive.br"))
+                .with_extract_options(
+                    ExtractOptions::default().with_overwrite(Overwrite::Skip)
+                )
+                .plan_decompress(d.path())
+                .unwrap()
+                .actions(),
+            [Action::Skip(d.path().join("archive"))]
+        );
+    }
+
+    #[test]
+    fn dictionary() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let dictionary = Dictionary::read("Cargo.toml").unwrap();
+        let br = Brotli::new(d.to_string() + "/archive.br")
+            .with_dictionary(dictionary.clone());
+
+        assert!(br.compress("Cargo.toml").is_ok());
+        assert!(br.test().is_ok());
+        assert!(br.decompress(d).is_ok());
+        assert_eq!(
+            "Cargo.toml".read_silently().unwrap(),
+            (d.to_string() + "/archive").read_silently().unwrap()
+        );
+        assert!(
+            std::fs::metadata(d.to_string() + "/archive.br")
+                .unwrap()
+                .len()
+                < 128
+        );
+        assert_eq!(
+            Brotli::new(d.to_string() + "/archive.br").test(),
+            Err(ExitCode::DataErr)
+        );
+        assert_eq!(
+            Brotli::new(d.to_string() + "/archive.br")
+                .with_dictionary(Dictionary::new(b"other".to_vec()))
+                .test(),
+            Err(ExitCode::DataErr)
+        );
+    }
+
+    #[test]
+    fn dictionary_not_required() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+
+        assert!(Brotli::new(d.to_string() + "/archive.br")
+            .compress("LICENSE")
+            .is_ok());
+        assert!(Brotli::new(d.to_string() + "/archive.br")
+            .with_dictionary(Dictionary::read("Cargo.toml").unwrap())
+            .test()
+            .is_ok());
+    }
+
+    #[test]
+    fn exists_failure() {
+        assert!(!Brotli::new("does_not_exist.br").exists());
+    }
+
+    #[test]
+    fn life_cycle() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let br = Brotli::new(d.to_string() + "/archive.br");
+
+        assert!(!br.exists());
+        assert!(br.compress("LICENSE").is_ok());
+        assert!(br.exists());
+        assert!(br.decompress(d).is_ok());
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            (d.to_string() + "/archive").read_silently().unwrap()
+        );
+        assert!(br.remove().is_ok());
+    }
+
+    #[test]
+    fn overwrite() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let path = d.to_string() + "/LICENSE";
+        let brotli = |overwrite| {
+            Brotli::new(d.to_string() + "/LICENSE.br").with_extract_options(
+                ExtractOptions::default().with_overwrite(overwrite),
+            )
+        };
+
+        assert!(brotli(Overwrite::Replace).compress("LICENSE").is_ok());
+
+        std::fs::write(&path, "existing").unwrap();
+        assert_eq!(
+            brotli(Overwrite::Fail).decompress(d),
+            Err(ExitCode::CantCreat)
+        );
+        assert!(brotli(Overwrite::Ask).decompress(d).is_ok());
+        assert_eq!(path.read_silently().unwrap(), "existing");
+        assert!(Brotli::new(d.to_string() + "/LICENSE.br")
+            .with_extract_options(
+                ExtractOptions::default()
+                    .with_confirm(|_| Ok(false))
+                    .with_overwrite(Overwrite::Ask)
+            )
+            .decompress(d)
+            .is_ok());
+        assert_eq!(path.read_silently().unwrap(), "existing");
+        assert!(brotli(Overwrite::Skip).decompress(d).is_ok());
+        assert_eq!(path.read_silently().unwrap(), "existing");
+        assert!(Brotli::new(d.to_string() + "/LICENSE.br")
+            .with_extract_options(
+                ExtractOptions::default()
+                    .with_confirm(|_| Ok(true))
+                    .with_overwrite(Overwrite::Ask)
+            )
+            .decompress(d)
+            .is_ok());
+        assert_eq!(
+            path.read_silently().unwrap(),
+            "LICENSE".read_silently().unwrap()
+        );
+        std::fs::write(&path, "existing").unwrap();
+        assert!(brotli(Overwrite::Replace).decompress(d).is_ok());
+        assert_eq!(
+            path.read_silently().unwrap(),
+            "LICENSE".read_silently().unwrap()
+        );
+    }
+
+    #[test]
+    fn remove_failure() {
+        assert!(Brotli::new("does_not_exist.br").remove().is_err());
+    }
+
+    #[test]
+    fn test_corrupt_stream() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let br = Brotli::new(d.to_string() + "/archive.br");
+
+        assert!(br.compress("LICENSE").is_ok());
+
+        let mut bytes = std::fs::read(d.to_string() + "/archive.br").unwrap();
+        bytes.truncate(bytes.len() / 2);
+        std::fs::write(d.to_string() + "/archive.br", bytes).unwrap();
+
+        assert_eq!(br.test(), Err(ExitCode::DataErr));
+    }
+
+    #[test]
+    fn test_tar_br() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+        let br = Brotli::new(d.to_string() + "/archive.tar.br");
+
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(br.compress(d.to_string() + "/archive.tar").is_ok());
+        assert!(br.test().is_ok());
+    }
+
+    #[test]
+    fn test_tar_br_corrupt_header() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let br = Brotli::new(d.to_string() + "/archive.tar.br");
+
+        std::fs::write(d.to_string() + "/archive.tar", [1; 1024]).unwrap();
+
+        assert!(br.compress(d.to_string() + "/archive.tar").is_ok());
+        assert_eq!(br.test(), Err(ExitCode::DataErr));
+    }
+
+    #[test]
+    fn volumes() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let br =
+            Brotli::new(d.to_string() + "/archive.br").with_volume_size(1024);
+
+        assert!(br.compress("LICENSE").is_ok());
+        assert!(br.exists());
+        assert!(
+            !std::path::Path::new(&(d.to_string() + "/archive.br")).exists()
+        );
+        assert!(
+            std::path::Path::new(&(d.to_string() + "/archive.br.003")).exists()
+        );
+        assert!(Brotli::new(d.to_string() + "/archive.br.001")
+            .test()
+            .is_ok());
+        assert!(br.decompress(d).is_ok());
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            (d.to_string() + "/archive").read_silently().unwrap()
+        );
+        assert!(Brotli::new(d.to_string() + "/archive.br")
+            .compress("LICENSE")
+            .is_ok());
+        assert!(
+            !std::path::Path::new(&(d.to_string() + "/archive.br")).exists()
+        );
+        assert!(
+            std::path::Path::new(&(d.to_string() + "/archive.br.003")).exists()
+        );
+        assert!(br.remove().is_ok());
+        assert!(!br.exists());
+    }
+}
+
+mod conflict {
+    mod display {
+        use aeruginous_tbr::Conflict;
+
+        #[test]
+        fn error() {
+            assert_eq!(format!("{}", Conflict::Error), "error");
+        }
+
+        #[test]
+        fn first() {
+            assert_eq!(format!("{}", Conflict::First), "first wins");
+        }
+
+        #[test]
+        fn last() {
+            assert_eq!(format!("{}", Conflict::Last), "last wins");
+        }
+    }
+
+    mod from_str {
+        use aeruginous_tbr::Conflict;
+        use std::str::FromStr;
+
+        #[test]
+        fn error() {
+            assert_eq!(Conflict::from_str("error").unwrap(), Conflict::Error);
+            assert_eq!(Conflict::from_str("fail").unwrap(), Conflict::Error);
+        }
+
+        #[test]
+        fn failure() {
+            assert_eq!(
+                Conflict::from_str("newest").unwrap_err(),
+                "'newest' is not supported, yet"
+            );
+        }
+
+        #[test]
+        fn first() {
+            assert_eq!(Conflict::from_str("first").unwrap(), Conflict::First);
+            assert_eq!(
+                Conflict::from_str("first-wins").unwrap(),
+                Conflict::First
+            );
+            assert_eq!(Conflict::from_str("keep").unwrap(), Conflict::First);
+        }
+
+        #[test]
+        fn last() {
+            assert_eq!(Conflict::from_str("last").unwrap(), Conflict::Last);
+            assert_eq!(
+                Conflict::from_str("last-wins").unwrap(),
+                Conflict::Last
+            );
+            assert_eq!(Conflict::from_str("replace").unwrap(), Conflict::Last);
+        }
+    }
+}
+
+mod cpio {
+    use aeruginous_io::PathBufLikeReader;
+    use aeruginous_tbr::{Cpio, ExtractOptions, Tar};
+    use std::path::PathBuf;
+    use sysexits::ExitCode;
     use tempfile::tempdir;
 
+    fn newc(name: &str, mode: u32, data: &[u8]) -> Vec<u8> {
+        let mut result = format!(
+            "070701{:08x}{mode:08x}{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}\
+             {:08x}{:08x}{:08x}{:08x}{name}\0",
+            0,
+            0,
+            0,
+            1,
+            0,
+            data.len(),
+            0,
+            0,
+            0,
+            0,
+            name.len() + 1,
+            0
+        )
+        .into_bytes();
+
+        result.resize(result.len().div_ceil(4) * 4, 0);
+        result.extend_from_slice(data);
+        result.resize(result.len().div_ceil(4) * 4, 0);
+        result
+    }
+
+    fn odc(name: &str, mode: u32, data: &[u8]) -> Vec<u8> {
+        let mut result = format!(
+            "070707{:06o}{:06o}{mode:06o}{:06o}{:06o}{:06o}{:06o}{:011o}\
+             {:06o}{:011o}{name}\0",
+            0,
+            0,
+            0,
+            0,
+            1,
+            0,
+            0,
+            name.len() + 1,
+            data.len()
+        )
+        .into_bytes();
+
+        result.extend_from_slice(data);
+        result
+    }
+
+    #[test]
+    fn convert() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let cpio = Cpio::new(d.to_string() + "/archive.cpio");
+        let tbr = Tar::new(d.to_string() + "/archive.tbr");
+        let mut bytes = newc(".", 0o40_755, b"");
+
+        bytes.extend(newc("bin", 0o40_755, b""));
+        bytes.extend(newc("bin/sh", 0o100_755, b"#!/bin/sh\n"));
+        bytes.extend(newc("dev/console", 0o20_600, b""));
+        bytes.extend(newc("TRAILER!!!", 0, b""));
+        std::fs::write(d.to_string() + "/archive.cpio", bytes).unwrap();
+
+        assert!(cpio.convert(&tbr).is_ok());
+        assert!(tbr.test().is_ok());
+        assert_eq!(cpio.list().unwrap(), tbr.list().unwrap());
+    }
+
+    #[test]
+    fn extract_options() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mut bytes = newc("setuid", 0o104_755, b"");
+
+        bytes.extend(newc("TRAILER!!!", 0, b""));
+        std::fs::write(d.to_string() + "/archive.cpio", bytes).unwrap();
+
+        for (options, mode) in [
+            (ExtractOptions::default(), 0o755),
+            (ExtractOptions::default().with_mask(0o022), 0o755),
+            (ExtractOptions::default().with_mask(0o027), 0o750),
+            (ExtractOptions::default().with_permissions(true), 0o4755),
+        ] {
+            assert!(Cpio::new(d.to_string() + "/archive.cpio")
+                .with_extract_options(options)
+                .extract(d)
+                .is_ok());
+
+            let metadata =
+                std::fs::metadata(d.to_string() + "/setuid").unwrap();
+
+            assert_eq!(metadata.permissions().mode() & 0o7777, mode);
+            assert_eq!(metadata.mtime(), 0);
+        }
+
+        assert!(Cpio::new(d.to_string() + "/archive.cpio")
+            .with_extract_options(ExtractOptions::default().with_mtime(false))
+            .extract(d)
+            .is_ok());
+        assert_ne!(
+            std::fs::metadata(d.to_string() + "/setuid")
+                .unwrap()
+                .mtime(),
+            0
+        );
+    }
+
+    #[test]
+    fn extract_members() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let cpio = Cpio::new(d.to_string() + "/archive.cpio");
+        let mut bytes = newc("bin/sh", 0o100_755, b"#!/bin/sh\n");
+
+        bytes.extend(newc("etc/hostname", 0o100_644, b"localhost\n"));
+        bytes.extend(newc("TRAILER!!!", 0, b""));
+        std::fs::write(d.to_string() + "/archive.cpio", bytes).unwrap();
+
+        assert!(cpio.extract_members(d, &["etc/hostname"]).is_ok());
+        assert!(PathBuf::from(d.to_string() + "/etc/hostname").exists());
+        assert!(!PathBuf::from(d.to_string() + "/bin").exists());
+        assert_eq!(
+            cpio.extract_members(d, &["does_not_exist.txt"]),
+            Err(ExitCode::DataErr)
+        );
+    }
+
+    #[test]
+    fn life_cycle() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let cpio = Cpio::new(d.to_string() + "/archive.cpio");
+        let mut bytes = newc("./LICENSE", 0o100_644, b"odd");
+
+        bytes.extend(newc("./directory", 0o40_755, b""));
+        bytes.extend(newc("./directory/link", 0o120_777, b"../LICENSE"));
+        bytes.extend(newc("TRAILER!!!", 0, b""));
+
+        assert!(!cpio.exists());
+        std::fs::write(d.to_string() + "/archive.cpio", bytes).unwrap();
+        assert!(cpio.exists());
+        assert_eq!(
+            cpio.list().unwrap(),
+            [
+                PathBuf::from("LICENSE"),
+                PathBuf::from("directory"),
+                PathBuf::from("directory/link")
+            ]
+        );
+        assert!(cpio.extract(d.to_string() + "/output").is_ok());
+        assert_eq!(
+            (d.to_string() + "/output/directory/link")
+                .read_silently()
+                .unwrap(),
+            "odd"
+        );
+        assert!(cpio.remove().is_ok());
+        assert!(!cpio.exists());
+    }
+
+    #[test]
+    fn odc_format() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let cpio = Cpio::new(d.to_string() + "/archive.cpio");
+        let mut bytes = odc("file", 0o100_644, b"content");
+
+        bytes.extend(odc("TRAILER!!!", 0, b""));
+        std::fs::write(d.to_string() + "/archive.cpio", bytes).unwrap();
+
+        assert_eq!(cpio.list().unwrap(), [PathBuf::from("file")]);
+    }
+
+    #[test]
+    fn parent_directory() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mut bytes = newc("../escape", 0o100_644, b"");
+
+        bytes.extend(newc("TRAILER!!!", 0, b""));
+        std::fs::write(d.to_string() + "/archive.cpio", bytes).unwrap();
+
+        assert_eq!(
+            Cpio::new(d.to_string() + "/archive.cpio").extract(d),
+            Err(ExitCode::DataErr)
+        );
+    }
+
+    #[test]
+    fn symlink_escape() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mut bytes =
+            newc("link", 0o120_777, (d.to_string() + "/outside").as_bytes());
+
+        std::fs::create_dir(d.to_string() + "/outside").unwrap();
+        bytes.extend(newc("link/pwned", 0o100_644, b"odd"));
+        bytes.extend(newc("TRAILER!!!", 0, b""));
+        std::fs::write(d.to_string() + "/archive.cpio", bytes).unwrap();
+
+        assert_eq!(
+            Cpio::new(d.to_string() + "/archive.cpio")
+                .extract(d.to_string() + "/output"),
+            Err(ExitCode::DataErr)
+        );
+        assert!(
+            !std::path::Path::new(&(d.to_string() + "/outside/pwned")).exists()
+        );
+    }
+
+    #[test]
+    fn truncated_data() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let bytes = newc("file", 0o100_644, b"content");
+
+        std::fs::write(d.to_string() + "/archive.cpio", &bytes[..120]).unwrap();
+
+        assert_eq!(
+            Cpio::new(d.to_string() + "/archive.cpio").list(),
+            Err(ExitCode::DataErr)
+        );
+    }
+}
+
+mod extract_options {
+    use aeruginous_tbr::{ExtractOptions, Overwrite};
+
+    #[test]
+    fn builder() {
+        let options = ExtractOptions::default()
+            .with_mask(0o10_022)
+            .with_mtime(false)
+            .with_numeric_owner(true)
+            .with_overwrite(Overwrite::Fail)
+            .with_ownership(true)
+            .with_permissions(true);
+
+        assert_eq!(options.mask(), 0o022);
+        assert!(!options.mtime());
+        assert!(options.numeric_owner());
+        assert_eq!(options.overwrite(), Overwrite::Fail);
+        assert!(options.ownership());
+        assert!(options.permissions());
+    }
+
+    #[test]
+    fn default() {
+        let options = ExtractOptions::default();
+
+        assert_eq!(options.mask(), 0);
+        assert!(options.mtime());
+        assert!(!options.numeric_owner());
+        assert_eq!(options.overwrite(), Overwrite::Replace);
+        assert!(!options.ownership());
+        assert!(!options.permissions());
+    }
+}
+
+mod overwrite {
+    mod display {
+        use aeruginous_tbr::Overwrite;
+
+        #[test]
+        fn ask() {
+            assert_eq!(format!("{}", Overwrite::Ask), "ask");
+        }
+
+        #[test]
+        fn fail() {
+            assert_eq!(format!("{}", Overwrite::Fail), "fail");
+        }
+
+        #[test]
+        fn keep_newer() {
+            assert_eq!(format!("{}", Overwrite::KeepNewer), "keep newer");
+        }
+
+        #[test]
+        fn rename() {
+            assert_eq!(format!("{}", Overwrite::Rename), "rename");
+        }
+
+        #[test]
+        fn replace() {
+            assert_eq!(format!("{}", Overwrite::Replace), "replace");
+        }
+
+        #[test]
+        fn skip() {
+            assert_eq!(format!("{}", Overwrite::Skip), "skip");
+        }
+    }
+
+    mod from_str {
+        use aeruginous_tbr::Overwrite;
+        use std::str::FromStr;
+
+        #[test]
+        fn ask() {
+            assert_eq!(Overwrite::from_str("ask").unwrap(), Overwrite::Ask);
+            assert_eq!(
+                Overwrite::from_str("interactive").unwrap(),
+                Overwrite::Ask
+            );
+            assert_eq!(Overwrite::from_str("prompt").unwrap(), Overwrite::Ask);
+        }
+
+        #[test]
+        fn fail() {
+            assert_eq!(Overwrite::from_str("error").unwrap(), Overwrite::Fail);
+            assert_eq!(Overwrite::from_str("fail").unwrap(), Overwrite::Fail);
+            assert_eq!(
+                Overwrite::from_str("keep-old-files").unwrap(),
+                Overwrite::Fail
+            );
+        }
+
+        #[test]
+        fn failure() {
+            assert_eq!(
+                Overwrite::from_str("sometimes").unwrap_err(),
+                "'sometimes' is not supported, yet"
+            );
+        }
+
+        #[test]
+        fn keep_newer() {
+            assert_eq!(
+                Overwrite::from_str("keep-newer").unwrap(),
+                Overwrite::KeepNewer
+            );
+            assert_eq!(
+                Overwrite::from_str("keep-newer-files").unwrap(),
+                Overwrite::KeepNewer
+            );
+            assert_eq!(
+                Overwrite::from_str("newer").unwrap(),
+                Overwrite::KeepNewer
+            );
+        }
+
+        #[test]
+        fn rename() {
+            assert_eq!(
+                Overwrite::from_str("backup").unwrap(),
+                Overwrite::Rename
+            );
+            assert_eq!(
+                Overwrite::from_str("rename").unwrap(),
+                Overwrite::Rename
+            );
+            assert_eq!(
+                Overwrite::from_str("suffix").unwrap(),
+                Overwrite::Rename
+            );
+        }
+
+        #[test]
+        fn replace() {
+            assert_eq!(
+                Overwrite::from_str("overwrite").unwrap(),
+                Overwrite::Replace
+            );
+            assert_eq!(
+                Overwrite::from_str("replace").unwrap(),
+                Overwrite::Replace
+            );
+        }
+
+        #[test]
+        fn skip() {
+            assert_eq!(Overwrite::from_str("skip").unwrap(), Overwrite::Skip);
+            assert_eq!(
+                Overwrite::from_str("skip-old-files").unwrap(),
+                Overwrite::Skip
+            );
+        }
+    }
+}
+
+mod tar {
+    use aeruginous_io::PathBufLikeReader;
+    use aeruginous_tbr::{
+        Action, Brotli, Compression, Conflict, Dictionary, ExtractOptions,
+        Locking, Overwrite, Tar,
+    };
+    use std::{
+        os::unix::fs::symlink,
+        path::PathBuf,
+        time::{Duration, Instant},
+    };
+    use sysexits::ExitCode;
+    use tempfile::tempdir;
+
+    #[test]
+    fn atomic_conversion() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+        let corrupt = Tar::new(d.to_string() + "/corrupt.tar.br");
+
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        std::fs::write(d.to_string() + "/corrupt.tar.br", [0xff; 4096])
+            .unwrap();
+
+        let before = std::fs::read(d.to_string() + "/archive.tar").unwrap();
+
+        assert!(corrupt.convert(&tar).is_err());
+        assert_eq!(
+            std::fs::read(d.to_string() + "/archive.tar").unwrap(),
+            before
+        );
+        assert!(corrupt
+            .convert(&Tar::new(d.to_string() + "/never_created.tar"))
+            .is_err());
+        assert_eq!(std::fs::read_dir(d).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn cat() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar.br");
+        let mut output = Vec::new();
+
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(tar.cat("LICENSE", &mut output).is_ok());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "LICENSE".read_silently().unwrap()
+        );
+        assert_eq!(
+            tar.cat("does_not_exist.txt", &mut Vec::new()),
+            Err(ExitCode::DataErr)
+        );
+    }
+
+    #[test]
+    fn concurrent_updates() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let files = ["CITATION.cff", "Cargo.lock", "Cargo.toml", "LICENSE"];
+
+        std::thread::scope(|s| {
+            for file in files {
+                s.spawn(move || {
+                    Tar::new(d.to_string() + "/archive.tar.br")
+                        .add_files(&[file])
+                        .unwrap();
+                });
+            }
+        });
+
+        let mut list =
+            Tar::new(d.to_string() + "/archive.tar.br").list().unwrap();
+
+        list.sort();
+        assert_eq!(list, files.map(PathBuf::from));
+    }
+
+    #[test]
+    fn convert() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+        let tar_br = Tar::new(d.to_string() + "/archive.tar.br");
+        let tbr = Tar::new(d.to_string() + "/archive.tbr");
+
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(tar.convert(&tar_br).is_ok());
+        assert!(Brotli::new(d.to_string() + "/archive.tar.br")
+            .test()
+            .is_ok());
+        assert!(tar_br.convert(&tbr).is_ok());
+        assert_eq!(tar.list().unwrap(), tbr.list().unwrap());
+        assert_eq!(tar.convert(&tar), Err(ExitCode::Usage));
+    }
+
+    #[test]
+    fn dictionary() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let dictionary = Dictionary::read("LICENSE").unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar.br")
+            .with_dictionary(dictionary.clone());
+        let tbr = Tar::new(d.to_string() + "/archive.tbr");
+
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        assert!(tar.test().is_ok());
+        assert!(
+            std::fs::metadata(d.to_string() + "/archive.tar.br")
+                .unwrap()
+                .len()
+                < 1024
+        );
+        assert!(Tar::new(d.to_string() + "/archive.tar.br").list().is_err());
+        assert!(tar.convert(&tbr).is_ok());
+        assert_eq!(tbr.list().unwrap(), [PathBuf::from("LICENSE")]);
+    }
+
+    #[test]
+    fn dry_run() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar.gz");
+
+        assert_eq!(
+            tar.plan_add_files(&["Cargo.toml", "LICENSE"])
+                .unwrap()
+                .actions(),
+            [
+                Action::Add(PathBuf::from("Cargo.toml")),
+                Action::Add(PathBuf::from("LICENSE"))
+            ]
+        );
+        assert!(tar.plan_remove().is_err());
+        assert!(tar.plan_extract(d).is_err());
+        assert_eq!(
+            tar.plan_write().actions(),
+            [Action::Add(PathBuf::from(
+                d.to_string() + "/archive.tar.gz"
+            ))]
+        );
+        assert!(!tar.exists());
+
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        std::fs::write(d.to_string() + "/LICENSE", "").unwrap();
+
+        let before = tar.list().unwrap();
+
+        assert_eq!(
+            tar.plan_add_files(&["Cargo.toml", "LICENSE"])
+                .unwrap()
+                .actions(),
+            [
+                Action::Add(PathBuf::from("Cargo.toml")),
+                Action::Replace(PathBuf::from("LICENSE"))
+            ]
+        );
+        assert_eq!(tar.list().unwrap(), before);
+        assert_eq!(
+            tar.plan_extract(d.to_string() + "/output")
+                .unwrap()
+                .actions(),
+            [Action::Extract(PathBuf::from(
+                d.to_string() + "/output/LICENSE"
+            ))]
+        );
+        assert_eq!(
+            tar.plan_extract_members(d, &["LICENSE"]).unwrap().actions(),
+            [Action::Replace(PathBuf::from(d.to_string() + "/LICENSE"))]
+        );
+        assert_eq!(
+            Tar::new(d.to_string() + "/archive.tar.gz")
+                .with_extract_options(
+                    ExtractOptions::default().with_overwrite(Overwrite::Skip)
+                )
+                .plan_extract(d)
+                .unwrap()
+                .to_string(),
+            format!("skip      {d}/LICENSE\n")
+        );
+        assert_eq!(
+            tar.with_extract_options(
+                ExtractOptions::default().with_overwrite(Overwrite::Fail)
+            )
+            .plan_extract(d)
+            .unwrap()
+            .actions(),
+            [Action::Fail(PathBuf::from(d.to_string() + "/LICENSE"))]
+        );
+        assert!(!std::path::Path::new(&(d.to_string() + "/output")).exists());
+        assert!(std::fs::read_to_string(d.to_string() + "/LICENSE")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            Tar::new(d.to_string() + "/archive.tar.gz")
+                .plan_remove()
+                .unwrap()
+                .actions(),
+            [Action::Delete(PathBuf::from(
+                d.to_string() + "/archive.tar.gz"
+            ))]
+        );
+        assert!(Tar::new(d.to_string() + "/archive.tar.gz").exists());
+    }
+
+    #[test]
+    fn exists_failure() {
+        assert!(!Tar::new("does_not_exist.tar").exists());
+    }
+
+    #[test]
+    fn extract_options() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+
+        header.set_mode(0o4755);
+        header.set_mtime(1_000_000_000);
+        header.set_size(3);
+        builder
+            .append_data(&mut header, "directory/setuid", &b"odd"[..])
+            .unwrap();
+        std::fs::write(
+            d.to_string() + "/archive.tar",
+            builder.into_inner().unwrap(),
+        )
+        .unwrap();
+
+        let path = d.to_string() + "/directory/setuid";
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        for (options, mode) in [
+            (ExtractOptions::default(), 0o755),
+            (ExtractOptions::default().with_mask(0o027), 0o750),
+            (ExtractOptions::default().with_permissions(true), 0o4755),
+        ] {
+            assert!(Tar::new(d.to_string() + "/archive.tar")
+                .with_extract_options(options)
+                .extract(d)
+                .is_ok());
+
+            let metadata = std::fs::metadata(&path).unwrap();
+
+            assert_eq!(metadata.permissions().mode() & 0o7777, mode);
+            assert_eq!(metadata.mtime(), 1_000_000_000);
+        }
+
+        assert!(tar
+            .with_extract_options(
+                ExtractOptions::default()
+                    .with_mtime(false)
+                    .with_overwrite(Overwrite::Fail)
+            )
+            .extract(d)
+            .is_err());
+        assert!(Tar::new(d.to_string() + "/archive.tar")
+            .with_extract_options(ExtractOptions::default().with_mtime(false))
+            .extract_members(d, &["directory/setuid"])
+            .is_ok());
+        assert_ne!(std::fs::metadata(&path).unwrap().mtime(), 1_000_000_000);
+    }
+
+    #[test]
+    fn extract_members() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(tar.extract_members(d, &["LICENSE"]).is_ok());
+        assert!(PathBuf::from(d.to_string() + "/LICENSE").exists());
+        assert!(!PathBuf::from(d.to_string() + "/Cargo.toml").exists());
+        assert_eq!(
+            tar.extract_members(d, &["does_not_exist.txt"]),
+            Err(ExitCode::DataErr)
+        );
+    }
+
+    #[test]
+    fn life_cycle() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        assert!(!tar.exists());
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        assert_eq!(tar.list().unwrap(), [PathBuf::from("LICENSE")]);
+        assert!(tar.add_files(&[".renovaterc.json5"]).is_ok());
+        assert_eq!(
+            tar.list().unwrap(),
+            [PathBuf::from(".renovaterc.json5"), PathBuf::from("LICENSE")]
+        );
+        assert!(tar.extract(d).is_ok());
+        assert_eq!(
+            ".renovaterc.json5".read_silently().unwrap(),
+            (d.to_string() + "/.renovaterc.json5")
+                .read_silently()
+                .unwrap()
+        );
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            (d.to_string() + "/LICENSE").read_silently().unwrap()
+        );
+        assert!(tar.remove().is_ok());
+    }
+
+    #[test]
+    fn level() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let fast = Tar::new(d.to_string() + "/fast.tar.br").with_level(0);
+        let best = Tar::new(d.to_string() + "/best.tar.br");
+
+        assert!(fast.add_files(&["LICENSE"]).is_ok());
+        assert!(best.add_files(&["LICENSE"]).is_ok());
+        assert!(
+            std::fs::metadata(d.to_string() + "/fast.tar.br")
+                .unwrap()
+                .len()
+                > std::fs::metadata(d.to_string() + "/best.tar.br")
+                    .unwrap()
+                    .len()
+        );
+        assert_eq!(fast.list().unwrap(), best.list().unwrap());
+    }
+
+    #[test]
+    fn locking() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let lock = std::fs::File::create(d.to_string() + "/.archive.tar.lock")
+            .unwrap();
+        let tar = || Tar::new(d.to_string() + "/archive.tar");
+
+        assert!(fs4::FileExt::try_lock(&lock).is_ok());
+        assert_eq!(
+            tar().with_locking(Locking::NoWait).add_files(&["LICENSE"]),
+            Err(ExitCode::TempFail)
+        );
+
+        let start = Instant::now();
+
+        assert_eq!(
+            tar()
+                .with_locking(Locking::Timeout(Duration::from_millis(200)))
+                .add_files(&["LICENSE"]),
+            Err(ExitCode::TempFail)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert!(!tar().exists());
+
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            drop(lock);
+        });
+
+        assert!(tar().add_files(&["LICENSE"]).is_ok());
+        assert_eq!(tar().list().unwrap(), [PathBuf::from("LICENSE")]);
+        assert!(tar().with_locking(Locking::NoWait).remove().is_ok());
+        assert_eq!(std::fs::read_dir(d).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn merge() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let first = Tar::new(d.to_string() + "/first.tar");
+        let second = Tar::new(d.to_string() + "/second.tbr");
+        let target = Tar::new(d.to_string() + "/target.tar.br");
+        let sources = [first, second];
+
+        assert!(sources[0].add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(sources[1].add_files(&["CITATION.cff", "LICENSE"]).is_ok());
+        assert_eq!(
+            target.merge(&sources, Conflict::Error),
+            Err(ExitCode::DataErr)
+        );
+        assert!(!target.exists());
+        assert!(target.merge(&sources, Conflict::First).is_ok());
+        assert_eq!(
+            target.list().unwrap(),
+            [
+                PathBuf::from("Cargo.toml"),
+                PathBuf::from("LICENSE"),
+                PathBuf::from("CITATION.cff")
+            ]
+        );
+        assert!(target.merge(&sources, Conflict::Last).is_ok());
+        assert_eq!(
+            target.list().unwrap(),
+            [
+                PathBuf::from("Cargo.toml"),
+                PathBuf::from("CITATION.cff"),
+                PathBuf::from("LICENSE")
+            ]
+        );
+        assert!(target.test().is_ok());
+        assert_eq!(
+            sources[0].merge(&sources, Conflict::First),
+            Err(ExitCode::Usage)
+        );
+        assert_eq!(std::fs::read_dir(d).unwrap().count(), 3);
+
+        let existing = Tar::new(d.to_string() + "/existing.tar");
+
+        assert!(existing.add_files(&["Cargo.lock", "LICENSE"]).is_ok());
+        assert_eq!(
+            existing.merge(&sources, Conflict::Error),
+            Err(ExitCode::DataErr)
+        );
+        assert!(existing.merge(&sources, Conflict::First).is_ok());
+        assert_eq!(
+            existing.list().unwrap(),
+            [
+                PathBuf::from("Cargo.lock"),
+                PathBuf::from("LICENSE"),
+                PathBuf::from("Cargo.toml"),
+                PathBuf::from("CITATION.cff")
+            ]
+        );
+    }
+
+    #[test]
+    fn overwrite() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+        let path = d.to_string() + "/LICENSE";
+        let extract = |overwrite| {
+            Tar::new(d.to_string() + "/archive.tar")
+                .with_extract_options(
+                    ExtractOptions::default().with_overwrite(overwrite),
+                )
+                .extract(d)
+        };
+
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+
+        std::fs::write(&path, "existing").unwrap();
+        assert!(extract(Overwrite::Skip).is_ok());
+        assert_eq!(path.read_silently().unwrap(), "existing");
+        assert!(extract(Overwrite::KeepNewer).is_ok());
+        assert_eq!(path.read_silently().unwrap(), "existing");
+        assert!(extract(Overwrite::Rename).is_ok());
+        assert_eq!(
+            path.read_silently().unwrap(),
+            "LICENSE".read_silently().unwrap()
+        );
+        assert_eq!(
+            (d.to_string() + "/LICENSE.~1~").read_silently().unwrap(),
+            "existing"
+        );
+        assert!(extract(Overwrite::Rename).is_ok());
+        assert!(PathBuf::from(d.to_string() + "/LICENSE.~2~").exists());
+
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(std::time::UNIX_EPOCH)
+            .unwrap();
+        assert!(extract(Overwrite::KeepNewer).is_ok());
+        assert_ne!(
+            std::fs::metadata(&path).unwrap().modified().unwrap(),
+            std::time::UNIX_EPOCH
+        );
+        assert_eq!(extract(Overwrite::Fail), Err(ExitCode::CantCreat));
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.clone() + "/nested", "existing").unwrap();
+        assert!(extract(Overwrite::Replace).is_ok());
+        assert_eq!(
+            path.read_silently().unwrap(),
+            "LICENSE".read_silently().unwrap()
+        );
+    }
+
+    #[test]
+    fn plan_merge() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let target = Tar::new(d.to_string() + "/target.tar");
+        let sources = [
+            Tar::new(d.to_string() + "/first.tar"),
+            Tar::new(d.to_string() + "/second.tar"),
+        ];
+
+        assert!(sources[0].add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(sources[1].add_files(&["LICENSE"]).is_ok());
+        assert_eq!(
+            target.plan_merge(&sources, Conflict::Error),
+            Err(ExitCode::DataErr)
+        );
+        assert_eq!(
+            sources[0].plan_merge(&sources, Conflict::First),
+            Err(ExitCode::Usage)
+        );
+        assert_eq!(
+            target
+                .plan_merge(&sources, Conflict::Last)
+                .unwrap()
+                .actions(),
+            [
+                Action::Add(PathBuf::from("Cargo.toml")),
+                Action::Skip(PathBuf::from("LICENSE")),
+                Action::Add(PathBuf::from("LICENSE")),
+                Action::Add(PathBuf::from(d.to_string() + "/target.tar"))
+            ]
+        );
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn plan_rename() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert_eq!(
+            tar.plan_rename(&[("does_not_exist", "anything")]),
+            Err(ExitCode::DataErr)
+        );
+        assert_eq!(
+            tar.plan_rename(&[("LICENSE", "COPYING")])
+                .unwrap()
+                .to_string(),
+            format!(
+                "rename    LICENSE -> COPYING\nreplace   {d}/archive.tar\n"
+            )
+        );
+        assert_eq!(
+            tar.list().unwrap(),
+            [PathBuf::from("Cargo.toml"), PathBuf::from("LICENSE")]
+        );
+    }
+
+    #[test]
+    fn permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mode = |path: &str| {
+            std::fs::metadata(path).unwrap().permissions().mode() & 0o7777
+        };
+        let path = d.to_string() + "/archive.tar";
+        let tar = Tar::new(&path);
+
+        std::fs::File::create(d.to_string() + "/reference").unwrap();
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        assert_eq!(mode(&path), mode(&(d.to_string() + "/reference")));
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640))
+            .unwrap();
+        assert!(tar.add_files(&["Cargo.toml"]).is_ok());
+        assert_eq!(mode(&path), 0o640);
+        assert!(tar.rename(&[("LICENSE", "COPYING")]).is_ok());
+        assert_eq!(mode(&path), 0o640);
+    }
+
+    #[test]
+    fn remove_failure() {
+        assert!(Tar::new("does_not_exist.tar").remove().is_err());
+    }
+
+    #[test]
+    fn rename() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+        let mut output = Vec::new();
+
+        assert!(tar.add_files(&["LICENSE", "src"]).is_ok());
+
+        let before = tar.list().unwrap();
+
+        assert_eq!(
+            tar.rename(&[("does_not_exist", "anything")]),
+            Err(ExitCode::DataErr)
+        );
+        assert_eq!(
+            tar.rename(&[("LICENSE", "src/lib.rs")]),
+            Err(ExitCode::DataErr)
+        );
+        assert_eq!(
+            tar.rename(&[("LICENSE", "COPYING"), ("src/lib.rs", "COPYING")]),
+            Err(ExitCode::DataErr)
+        );
+        assert_eq!(tar.list().unwrap(), before);
+        assert!(tar
+            .rename(&[("src", "source"), ("LICENSE", "COPYING")])
+            .is_ok());
+        assert_eq!(
+            tar.list().unwrap(),
+            before
+                .iter()
+                .map(|p| if p == &PathBuf::from("LICENSE") {
+                    PathBuf::from("COPYING")
+                } else {
+                    PathBuf::from("source").join(p.strip_prefix("src").unwrap())
+                })
+                .collect::<Vec<_>>()
+        );
+        assert!(tar.cat("COPYING", &mut output).is_ok());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "LICENSE".read_silently().unwrap()
+        );
+        assert!(tar.test().is_ok());
+    }
+
     #[test]
-    fn exists_failure() {
-        assert!(!Brotli::new("does_not_exist.br").exists());
+    fn rename_hard_link() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+        let mut builder = tar::Builder::new(
+            std::fs::File::create(d.to_string() + "/archive.tar").unwrap(),
+        );
+        let mut header = tar::Header::new_gnu();
+
+        header.set_size(4);
+        header.set_mode(0o644);
+        builder
+            .append_data(&mut header, "old/data", "data".as_bytes())
+            .unwrap();
+        header.set_entry_type(tar::EntryType::Link);
+        header.set_size(0);
+        builder
+            .append_link(&mut header, "old/link", "old/data")
+            .unwrap();
+        builder.finish().unwrap();
+        drop(builder);
+
+        assert!(tar.rename(&[("old", "new")]).is_ok());
+
+        let mut archive = tar::Archive::new(
+            std::fs::File::open(d.to_string() + "/archive.tar").unwrap(),
+        );
+        let links = archive
+            .entries()
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (
+                    e.path().unwrap().into_owned(),
+                    e.link_name().unwrap().map(|l| l.into_owned()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            links,
+            [
+                (PathBuf::from("new/data"), None),
+                (PathBuf::from("new/link"), Some(PathBuf::from("new/data")))
+            ]
+        );
     }
 
     #[test]
-    fn life_cycle() {
+    fn seekable() {
         let d = tempdir().unwrap();
         let d = d.path().to_str().unwrap();
-        let br = Brotli::new(d.to_string() + "/archive.br");
+        let tar = Tar::new(d.to_string() + "/archive.tar.br").with_seekable();
+        let mut output = Vec::new();
 
-        assert!(!br.exists());
-        assert!(br.compress("LICENSE").is_ok());
-        assert!(br.exists());
-        assert!(br.decompress(d).is_ok());
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE", "src"]).is_ok());
+        assert!(tar.test().is_ok());
+        assert_eq!(
+            tar.list().unwrap(),
+            Tar::new(d.to_string() + "/archive.tar.br").list().unwrap()
+        );
+        assert!(tar.cat("src/lib.rs", &mut output).is_ok());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "src/lib.rs".read_silently().unwrap()
+        );
+        assert!(tar.extract_members(d, &["LICENSE"]).is_ok());
         assert_eq!(
             "LICENSE".read_silently().unwrap(),
-            (d.to_string() + "/archive").read_silently().unwrap()
+            (d.to_string() + "/LICENSE").read_silently().unwrap()
+        );
+        assert!(Brotli::new(d.to_string() + "/archive.tar.br")
+            .decompress(d)
+            .is_ok());
+        assert_eq!(
+            Tar::new(d.to_string() + "/archive.tar").list().unwrap(),
+            tar.list().unwrap()
         );
-        assert!(br.remove().is_ok());
     }
 
+    #[cfg(feature = "gzip")]
     #[test]
-    fn remove_failure() {
-        assert!(Brotli::new("does_not_exist.br").remove().is_err());
-    }
-}
+    fn tar_gz_life_cycle() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar.gz");
+        let tgz = Tar::new(d.to_string() + "/archive.tgz");
 
-mod tar {
-    use aeruginous_io::PathBufLikeReader;
-    use aeruginous_tbr::Tar;
-    use std::{os::unix::fs::symlink, path::PathBuf};
-    use tempfile::tempdir;
+        assert_eq!(tar.compression(), Compression::Gzip);
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        assert!(tar.add_files(&["Cargo.toml"]).is_ok());
+        assert_eq!(
+            tar.list().unwrap(),
+            [PathBuf::from("Cargo.toml"), PathBuf::from("LICENSE")]
+        );
+        assert!(tar.test().is_ok());
+        assert!(tar.convert(&tgz).is_ok());
+        assert!(tgz.extract(d).is_ok());
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            (d.to_string() + "/LICENSE").read_silently().unwrap()
+        );
+    }
 
+    #[cfg(feature = "bzip2")]
     #[test]
-    fn exists_failure() {
-        assert!(!Tar::new("does_not_exist.tar").exists());
+    fn tar_bz2_life_cycle() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar.bz2");
+        let tbr = Tar::new(d.to_string() + "/archive.tbr");
+
+        assert_eq!(tar.compression(), Compression::Bzip2);
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        assert!(tar.add_files(&["Cargo.toml"]).is_ok());
+        assert_eq!(
+            tar.list().unwrap(),
+            [PathBuf::from("Cargo.toml"), PathBuf::from("LICENSE")]
+        );
+        assert!(tar.test().is_ok());
+        assert!(tar.convert(&tbr).is_ok());
+        assert!(tbr.extract(d).is_ok());
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            (d.to_string() + "/LICENSE").read_silently().unwrap()
+        );
     }
 
     #[test]
-    fn life_cycle() {
+    fn tar_br_life_cycle() {
         let d = tempdir().unwrap();
         let d = d.path().to_str().unwrap();
-        let tar = Tar::new(d.to_string() + "/archive.tar");
+        let tar = Tar::new(d.to_string() + "/archive.tar.br");
 
-        assert!(!tar.exists());
+        assert_eq!(tar.compression(), Compression::Brotli);
         assert!(tar.add_files(&["LICENSE"]).is_ok());
-        assert_eq!(tar.list().unwrap(), [PathBuf::from("LICENSE")]);
-        assert!(tar.add_files(&[".renovaterc.json5"]).is_ok());
+        assert!(tar.add_files(&["Cargo.toml"]).is_ok());
         assert_eq!(
             tar.list().unwrap(),
-            [PathBuf::from(".renovaterc.json5"), PathBuf::from("LICENSE")]
+            [PathBuf::from("Cargo.toml"), PathBuf::from("LICENSE")]
         );
+        assert!(tar.test().is_ok());
         assert!(tar.extract(d).is_ok());
-        assert_eq!(
-            ".renovaterc.json5".read_silently().unwrap(),
-            (d.to_string() + "/.renovaterc.json5")
-                .read_silently()
-                .unwrap()
-        );
         assert_eq!(
             "LICENSE".read_silently().unwrap(),
             (d.to_string() + "/LICENSE").read_silently().unwrap()
@@ -90,8 +1655,48 @@ mod tar {
     }
 
     #[test]
-    fn remove_failure() {
-        assert!(Tar::new("does_not_exist.tar").remove().is_err());
+    fn symlink_escape() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+
+        std::fs::create_dir(d.to_string() + "/outside").unwrap();
+        std::fs::write(d.to_string() + "/outside/victim", "odd").unwrap();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder
+            .append_link(&mut header, "link", d.to_string() + "/outside")
+            .unwrap();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(3);
+        builder
+            .append_data(&mut header, "link/victim", &b"new"[..])
+            .unwrap();
+        std::fs::write(
+            d.to_string() + "/archive.tar",
+            builder.into_inner().unwrap(),
+        )
+        .unwrap();
+
+        for overwrite in [Overwrite::Replace, Overwrite::Rename] {
+            assert_eq!(
+                Tar::new(d.to_string() + "/archive.tar")
+                    .with_extract_options(
+                        ExtractOptions::default().with_overwrite(overwrite)
+                    )
+                    .extract(d.to_string() + "/output"),
+                Err(ExitCode::DataErr)
+            );
+            assert_eq!(
+                (d.to_string() + "/outside/victim").read_silently().unwrap(),
+                "odd"
+            );
+            assert!(!std::path::Path::new(
+                &(d.to_string() + "/outside/victim.~1~")
+            )
+            .exists());
+        }
     }
 
     #[test]
@@ -125,6 +1730,170 @@ mod tar {
         );
     }
 
+    #[cfg(feature = "xz")]
+    #[test]
+    fn tar_xz_life_cycle() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar.xz");
+        let tar_br = Tar::new(d.to_string() + "/archive.tar.br");
+
+        assert_eq!(tar.compression(), Compression::Xz);
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        assert!(tar.add_files(&["Cargo.toml"]).is_ok());
+        assert_eq!(
+            tar.list().unwrap(),
+            [PathBuf::from("Cargo.toml"), PathBuf::from("LICENSE")]
+        );
+        assert!(tar.test().is_ok());
+        assert!(tar.convert(&tar_br).is_ok());
+        assert!(tar_br.extract(d).is_ok());
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            (d.to_string() + "/LICENSE").read_silently().unwrap()
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn tar_zst_life_cycle() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar.zst")
+            .with_level(19)
+            .with_window(27);
+        let tzst = Tar::new(d.to_string() + "/archive.tzst");
+
+        assert_eq!(tar.compression(), Compression::Zstd);
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        assert!(tar.add_files(&["Cargo.toml"]).is_ok());
+        assert_eq!(
+            tar.list().unwrap(),
+            [PathBuf::from("Cargo.toml"), PathBuf::from("LICENSE")]
+        );
+        assert!(tar.test().is_ok());
+        assert!(tar.convert(&tzst).is_ok());
+        assert!(tzst.extract(d).is_ok());
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            (d.to_string() + "/LICENSE").read_silently().unwrap()
+        );
+    }
+
+    #[test]
+    fn test() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(tar.test().is_ok());
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+
+        let mut bytes = std::fs::read(d.to_string() + "/archive.tar").unwrap();
+        bytes[0] ^= 1;
+        std::fs::write(d.to_string() + "/archive.tar", bytes).unwrap();
+
+        assert_eq!(tar.test(), Err(ExitCode::DataErr));
+    }
+
+    #[test]
+    fn test_does_not_exist() {
+        assert!(Tar::new("does_not_exist.tar").test().is_err());
+    }
+
+    #[test]
+    fn test_truncated_data() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+
+        let bytes = std::fs::read(d.to_string() + "/archive.tar").unwrap();
+        std::fs::write(d.to_string() + "/archive.tar", &bytes[..1024]).unwrap();
+
+        assert_eq!(tar.test(), Err(ExitCode::DataErr));
+    }
+
+    #[test]
+    fn test_truncated_trailer() {
+        use std::io::Write;
+
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mut builder = tar::Builder::new(Vec::new());
+
+        builder.append_path_with_name("LICENSE", "LICENSE").unwrap();
+
+        let mut bytes = builder.into_inner().unwrap();
+        let mut state = 1_u32;
+
+        bytes.extend((0..20_000).map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            state.to_be_bytes()[0]
+        }));
+
+        let mut compressed = Vec::new();
+        let mut encoder =
+            brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+
+        encoder.write_all(&bytes).unwrap();
+        drop(encoder);
+
+        let path = d.to_string() + "/archive.tar.br";
+        let tar = Tar::new(&path);
+
+        std::fs::write(&path, &compressed).unwrap();
+        assert!(tar.test().is_ok());
+        std::fs::write(&path, &compressed[..compressed.len() - 2000]).unwrap();
+        assert_eq!(tar.test(), Err(ExitCode::DataErr));
+    }
+
+    #[test]
+    fn xattrs() {
+        let d = tempfile::tempdir_in(".").unwrap();
+        let d = d
+            .path()
+            .strip_prefix(std::env::current_dir().unwrap())
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let file = d.to_string() + "/file.txt";
+        let tar = Tar::new(d.to_string() + "/archive.tar").with_xattrs();
+
+        std::fs::write(&file, "content").unwrap();
+
+        if xattr::set(&file, "user.tbr", b"value").is_err() {
+            return;
+        }
+
+        assert!(tar.add_files(&[&file]).is_ok());
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+
+        let plain = tempdir().unwrap();
+        let restored = tempdir().unwrap();
+        let member = |d: &tempfile::TempDir| d.path().join(&file);
+
+        assert!(Tar::new(d.to_string() + "/archive.tar")
+            .extract(plain.path())
+            .is_ok());
+        assert!(tar.extract(restored.path()).is_ok());
+        assert_eq!(xattr::get(member(&plain), "user.tbr").unwrap(), None);
+        assert_eq!(
+            xattr::get(member(&restored), "user.tbr").unwrap(),
+            Some(b"value".to_vec())
+        );
+    }
+
     #[test]
     fn update() {
         let d = tempdir().unwrap();
@@ -156,6 +1925,210 @@ mod tar {
         );
         assert!(tar.remove().is_ok());
     }
+
+    #[test]
+    fn volumes() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar =
+            Tar::new(d.to_string() + "/archive.tar").with_volume_size(4096);
+
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        assert!(tar.add_files(&["Cargo.toml"]).is_ok());
+        assert!(!PathBuf::from(d.to_string() + "/archive.tar").exists());
+        assert!(PathBuf::from(d.to_string() + "/archive.tar.001").exists());
+        assert!(PathBuf::from(d.to_string() + "/archive.tar.002").exists());
+        assert_eq!(
+            Tar::new(d.to_string() + "/archive.tar.001").list().unwrap(),
+            [PathBuf::from("Cargo.toml"), PathBuf::from("LICENSE")]
+        );
+        assert!(tar.test().is_ok());
+        assert!(tar.extract(d).is_ok());
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            (d.to_string() + "/LICENSE").read_silently().unwrap()
+        );
+        assert!(Tar::new(d.to_string() + "/archive.tar")
+            .add_files(&["CITATION.cff"])
+            .is_ok());
+        assert!(!PathBuf::from(d.to_string() + "/archive.tar").exists());
+
+        for n in 1..=3 {
+            assert!(
+                std::fs::metadata(format!("{d}/archive.tar.{n:03}"))
+                    .unwrap()
+                    .len()
+                    <= 4096
+            );
+        }
+
+        assert!(tar.remove().is_ok());
+        assert!(!tar.exists());
+        assert!(!PathBuf::from(d.to_string() + "/archive.tar.001").exists());
+
+        let plain = || Tar::new(d.to_string() + "/backup.123");
+
+        assert!(plain().add_files(&["LICENSE"]).is_ok());
+        assert!(PathBuf::from(d.to_string() + "/backup.123").exists());
+        assert!(!PathBuf::from(d.to_string() + "/backup").exists());
+        assert_eq!(plain().list().unwrap(), [PathBuf::from("LICENSE")]);
+        assert!(plain().remove().is_ok());
+        assert!(!PathBuf::from(d.to_string() + "/backup.123").exists());
+    }
+}
+
+#[cfg(feature = "zip")]
+mod zip {
+    use aeruginous_io::PathBufLikeReader;
+    use aeruginous_tbr::{ExtractOptions, Overwrite, Tar, Zip};
+    use std::path::PathBuf;
+    use sysexits::ExitCode;
+    use tempfile::tempdir;
+
+    #[test]
+    fn convert() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let zip = Zip::new(d.to_string() + "/archive.zip");
+        let tbr = Tar::new(d.to_string() + "/archive.tbr");
+
+        assert!(zip.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(zip.convert(&tbr).is_ok());
+        assert!(tbr.test().is_ok());
+        assert_eq!(zip.list().unwrap(), tbr.list().unwrap());
+    }
+
+    #[test]
+    fn exists_failure() {
+        assert!(!Zip::new("does_not_exist.zip").exists());
+    }
+
+    #[test]
+    fn extract_options() {
+        use std::os::unix::fs::MetadataExt;
+
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let zip = Zip::new(d.to_string() + "/archive.zip")
+            .with_extract_options(
+                ExtractOptions::default().with_overwrite(Overwrite::Fail),
+            );
+
+        assert!(zip.add_files(&["LICENSE"]).is_ok());
+        assert!(zip.extract(d).is_ok());
+        assert!(
+            (std::fs::metadata(d.to_string() + "/LICENSE")
+                .unwrap()
+                .mtime()
+                - std::fs::metadata("LICENSE").unwrap().mtime())
+            .abs()
+                <= 2
+        );
+        assert_eq!(zip.extract(d), Err(ExitCode::CantCreat));
+    }
+
+    #[test]
+    fn extract_members() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let zip = Zip::new(d.to_string() + "/archive.zip");
+
+        assert!(zip.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(zip.extract_members(d, &["LICENSE"]).is_ok());
+        assert!(PathBuf::from(d.to_string() + "/LICENSE").exists());
+        assert!(!PathBuf::from(d.to_string() + "/Cargo.toml").exists());
+        assert_eq!(
+            zip.extract_members(d, &["does_not_exist.txt"]),
+            Err(ExitCode::DataErr)
+        );
+    }
+
+    #[test]
+    fn level() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let stored = Zip::new(d.to_string() + "/stored.zip").with_level(0);
+        let deflated = Zip::new(d.to_string() + "/deflated.zip");
+
+        assert!(stored.add_files(&["LICENSE"]).is_ok());
+        assert!(deflated.add_files(&["LICENSE"]).is_ok());
+        assert!(
+            std::fs::metadata(d.to_string() + "/stored.zip")
+                .unwrap()
+                .len()
+                > std::fs::metadata(d.to_string() + "/deflated.zip")
+                    .unwrap()
+                    .len()
+        );
+        assert_eq!(stored.list().unwrap(), deflated.list().unwrap());
+    }
+
+    #[test]
+    fn life_cycle() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let zip = Zip::new(d.to_string() + "/archive.zip");
+
+        assert!(!zip.exists());
+        assert!(zip.add_files(&["LICENSE"]).is_ok());
+        assert_eq!(zip.list().unwrap(), [PathBuf::from("LICENSE")]);
+        assert!(zip.add_files(&["LICENSE", "Cargo.toml"]).is_ok());
+        assert_eq!(
+            zip.list().unwrap(),
+            [PathBuf::from("LICENSE"), PathBuf::from("Cargo.toml")]
+        );
+        assert!(zip.test().is_ok());
+        assert!(zip.extract(d).is_ok());
+        assert_eq!(
+            "LICENSE".read_silently().unwrap(),
+            (d.to_string() + "/LICENSE").read_silently().unwrap()
+        );
+        assert!(zip.remove().is_ok());
+        assert!(!zip.exists());
+    }
+
+    #[test]
+    fn symlink_escape() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mut writer = zip::ZipWriter::new(
+            std::fs::File::create(d.to_string() + "/archive.zip").unwrap(),
+        );
+        let options = zip::write::SimpleFileOptions::default();
+
+        std::fs::create_dir(d.to_string() + "/outside").unwrap();
+        writer
+            .add_symlink("link", d.to_string() + "/outside", options)
+            .unwrap();
+        writer.start_file("link/pwned.txt", options).unwrap();
+        std::io::Write::write_all(&mut writer, b"odd").unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(
+            Zip::new(d.to_string() + "/archive.zip")
+                .extract(d.to_string() + "/output"),
+            Err(ExitCode::DataErr)
+        );
+        assert!(
+            !std::path::Path::new(&(d.to_string() + "/outside/pwned.txt"))
+                .exists()
+        );
+    }
+
+    #[test]
+    fn test_crc_mismatch() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let zip = Zip::new(d.to_string() + "/archive.zip").with_level(0);
+
+        assert!(zip.add_files(&["LICENSE"]).is_ok());
+
+        let mut bytes = std::fs::read(d.to_string() + "/archive.zip").unwrap();
+        bytes[100] ^= 1;
+        std::fs::write(d.to_string() + "/archive.zip", bytes).unwrap();
+
+        assert_eq!(zip.test(), Err(ExitCode::DataErr));
+    }
 }
 
 /******************************************************************************/
@@ -0,0 +1,175 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+mod compression {
+    mod detect {
+        use aeruginous_tbr::Compression;
+
+        macro_rules! make_test {
+            ( $( $n:ident : $p:literal => $v:ident ),+ ) => {
+                $(
+                    #[test]
+                    fn $n() {
+                        assert_eq!(Compression::detect($p), Compression::$v);
+                    }
+                )+
+            };
+        }
+
+        make_test!(
+            br: "archive.br" => Brotli,
+            no_extension: "archive" => Uncompressed,
+            tar: "archive.tar" => Uncompressed,
+            tar_br: "archive.tar.br" => Brotli,
+            tbr: "archive.tbr" => Brotli
+        );
+
+        #[cfg(feature = "bzip2")]
+        make_test!(
+            bz2: "archive.bz2" => Bzip2,
+            tar_bz2: "archive.tar.bz2" => Bzip2,
+            tb2: "archive.tb2" => Bzip2,
+            tbz: "archive.tbz" => Bzip2,
+            tbz2: "archive.tbz2" => Bzip2
+        );
+
+        #[cfg(feature = "gzip")]
+        make_test!(
+            gz: "archive.gz" => Gzip,
+            tar_gz: "archive.tar.gz" => Gzip,
+            tgz: "archive.tgz" => Gzip
+        );
+
+        #[cfg(feature = "xz")]
+        make_test!(
+            tar_xz: "archive.tar.xz" => Xz,
+            txz: "archive.txz" => Xz,
+            xz: "archive.xz" => Xz
+        );
+
+        #[cfg(feature = "zstd")]
+        make_test!(
+            tar_zst: "archive.tar.zst" => Zstd,
+            tzst: "archive.tzst" => Zstd,
+            zst: "archive.zst" => Zstd
+        );
+    }
+
+    mod magic_bytes {
+        use aeruginous_tbr::{Compression, Tar};
+        use tempfile::tempdir;
+
+        macro_rules! make_test {
+            ( $( $n:ident : $f:literal $e:literal => $v:ident ),+ ) => {
+                $(
+                    #[cfg(feature = $f)]
+                    #[test]
+                    fn $n() {
+                        let d = tempdir().unwrap();
+                        let d = d.path().to_str().unwrap();
+
+                        assert!(Tar::new(d.to_string() + "/archive" + $e)
+                            .add_files(&["LICENSE"])
+                            .is_ok());
+
+                        std::fs::rename(
+                            d.to_string() + "/archive" + $e,
+                            d.to_string() + "/archive.tar",
+                        )
+                        .unwrap();
+
+                        assert_eq!(
+                            Compression::sniff(d.to_string() + "/archive.tar"),
+                            Some(Compression::$v)
+                        );
+                        assert_eq!(
+                            Compression::detect(d.to_string() + "/archive.tar"),
+                            Compression::$v
+                        );
+                        assert!(Tar::new(d.to_string() + "/archive.tar")
+                            .list()
+                            .is_ok());
+                    }
+                )+
+            };
+        }
+
+        make_test!(
+            bzip2: "bzip2" ".tar.bz2" => Bzip2,
+            gzip: "gzip" ".tar.gz" => Gzip,
+            xz: "xz" ".tar.xz" => Xz,
+            zstd: "zstd" ".tar.zst" => Zstd
+        );
+
+        #[test]
+        fn brotli() {
+            let d = tempdir().unwrap();
+            let d = d.path().to_str().unwrap();
+
+            assert!(Tar::new(d.to_string() + "/archive.tar.br")
+                .add_files(&["LICENSE"])
+                .is_ok());
+            assert_eq!(
+                Compression::sniff(d.to_string() + "/archive.tar.br"),
+                None
+            );
+        }
+
+        #[test]
+        fn does_not_exist() {
+            assert_eq!(Compression::sniff("does_not_exist.tar"), None);
+        }
+
+        #[test]
+        fn uncompressed() {
+            assert_eq!(Compression::sniff("LICENSE"), None);
+        }
+    }
+
+    mod display {
+        use aeruginous_tbr::Compression;
+
+        #[test]
+        fn brotli() {
+            assert_eq!(format!("{}", Compression::Brotli), "Brotli");
+        }
+
+        #[cfg(feature = "bzip2")]
+        #[test]
+        fn bzip2() {
+            assert_eq!(format!("{}", Compression::Bzip2), "Bzip2");
+        }
+
+        #[cfg(feature = "gzip")]
+        #[test]
+        fn gzip() {
+            assert_eq!(format!("{}", Compression::Gzip), "Gzip");
+        }
+
+        #[test]
+        fn uncompressed() {
+            assert_eq!(
+                format!("{}", Compression::Uncompressed),
+                "uncompressed"
+            );
+        }
+    }
+}
+
+/******************************************************************************/
@@ -0,0 +1,80 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+use aeruginous_tbr::Dictionary;
+use sysexits::ExitCode;
+use tempfile::tempdir;
+
+#[test]
+fn id() {
+    assert_eq!(
+        Dictionary::new(b"abc".to_vec()).id(),
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    );
+}
+
+#[test]
+fn read_write() {
+    let d = tempdir().unwrap();
+    let d = d.path().to_str().unwrap();
+    let dictionary = Dictionary::new(b"dictionary".to_vec());
+
+    assert!(dictionary.write(d.to_string() + "/dictionary").is_ok());
+    assert_eq!(
+        Dictionary::read(d.to_string() + "/dictionary").unwrap(),
+        dictionary
+    );
+}
+
+#[test]
+fn train() {
+    let d = tempdir().unwrap();
+    let d = d.path().to_str().unwrap();
+    let mut samples = Vec::new();
+
+    for i in 0..20 {
+        let sample = format!("{d}/{i}.json");
+        std::fs::write(
+            &sample,
+            format!(
+                "{{\"id\": {i}, \"description\": \"a build artifact produced \
+                 by the continuous integration pipeline\", \"tags\": \
+                 [\"release\", \"stable\"]}}"
+            ),
+        )
+        .unwrap();
+        samples.push(sample);
+    }
+
+    let dictionary = Dictionary::train(&samples, 1024).unwrap();
+
+    assert!(!dictionary.as_bytes().is_empty());
+    assert!(dictionary.as_bytes().len() <= 1024);
+    assert!(dictionary
+        .as_bytes()
+        .windows(11)
+        .any(|w| w == b"integration"));
+}
+
+#[test]
+fn train_without_samples() {
+    assert_eq!(Dictionary::train::<&str>(&[], 1024), Err(ExitCode::Usage));
+}
+
+/******************************************************************************/
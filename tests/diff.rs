@@ -0,0 +1,171 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+mod change {
+    use aeruginous_tbr::{Change, Property};
+    use std::path::PathBuf;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            format!("{}", Change::Added(PathBuf::from("LICENSE"))),
+            "added     LICENSE"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                Change::Modified(
+                    PathBuf::from("LICENSE"),
+                    vec![Property::Size, Property::Content]
+                )
+            ),
+            "modified  LICENSE (size, content)"
+        );
+        assert_eq!(
+            format!("{}", Change::Removed(PathBuf::from("LICENSE"))),
+            "removed   LICENSE"
+        );
+    }
+}
+
+mod difference {
+    use aeruginous_tbr::{Change, Difference, Property, Tar};
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn archive_and_directory() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        std::fs::create_dir(d.to_string() + "/tree").unwrap();
+        std::fs::copy("LICENSE", d.to_string() + "/tree/LICENSE").unwrap();
+        std::fs::write(d.to_string() + "/tree/Cargo.toml", "").unwrap();
+
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+
+        let difference = Difference::new(
+            d.to_string() + "/archive.tar",
+            d.to_string() + "/tree",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            difference.changes(),
+            [Change::Modified(p, properties), Change::Modified(q, _)]
+                if p == &PathBuf::from("Cargo.toml")
+                    && properties.contains(&Property::Size)
+                    && properties.contains(&Property::Content)
+                    && q == &PathBuf::from("LICENSE")
+        ));
+    }
+
+    #[test]
+    fn archives() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let old = Tar::new(d.to_string() + "/old.tar");
+        let new = Tar::new(d.to_string() + "/new.tar");
+
+        assert!(old.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+        assert!(new.add_files(&["LICENSE", "README.md"]).is_ok());
+
+        let difference = Difference::new(
+            d.to_string() + "/old.tar",
+            d.to_string() + "/new.tar",
+        )
+        .unwrap();
+
+        assert_eq!(
+            difference.changes(),
+            [
+                Change::Removed(PathBuf::from("Cargo.toml")),
+                Change::Added(PathBuf::from("README.md"))
+            ]
+        );
+        assert_eq!(
+            difference.to_json(),
+            "{\"added\":[\"README.md\"],\"removed\":[\"Cargo.toml\"],\"modified\":[]}"
+        );
+        assert_eq!(
+            format!("{difference}"),
+            "removed   Cargo.toml\nadded     README.md\n"
+        );
+    }
+
+    #[test]
+    fn equal() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+
+        let difference = Difference::new(
+            d.to_string() + "/archive.tar",
+            d.to_string() + "/archive.tar",
+        )
+        .unwrap();
+
+        assert!(difference.is_empty());
+        assert_eq!(
+            difference.to_json(),
+            "{\"added\":[],\"removed\":[],\"modified\":[]}"
+        );
+    }
+
+    #[test]
+    fn json_modified() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        std::fs::create_dir(d.to_string() + "/tree").unwrap();
+        std::fs::write(d.to_string() + "/tree/LICENSE", "").unwrap();
+
+        assert!(tar.add_files(&["LICENSE"]).is_ok());
+        assert!(Difference::new(
+            d.to_string() + "/archive.tar",
+            d.to_string() + "/tree"
+        )
+        .unwrap()
+        .to_json()
+        .starts_with("{\"added\":[],\"removed\":[],\"modified\":[{\"path\":\"LICENSE\",\"changes\":[\"size\""));
+    }
+
+    #[test]
+    fn side_does_not_exist() {
+        assert!(Difference::new("does_not_exist.tar", ".").is_err());
+    }
+}
+
+mod property {
+    use aeruginous_tbr::Property;
+
+    #[test]
+    fn display() {
+        assert_eq!(format!("{}", Property::Content), "content");
+        assert_eq!(format!("{}", Property::Mode), "mode");
+        assert_eq!(format!("{}", Property::Mtime), "mtime");
+        assert_eq!(format!("{}", Property::Size), "size");
+    }
+}
+
+/******************************************************************************/
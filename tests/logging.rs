@@ -0,0 +1,83 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+//! The library reports through the `log` facade instead of printing.  This
+//! binary installs its own logger in order to inspect the reported events.
+
+use aeruginous_tbr::Tar;
+use log::{Level, Log, Metadata, Record};
+use std::{path::PathBuf, sync::Mutex};
+use sysexits::ExitCode;
+
+struct Recorder(Mutex<Vec<(Level, String)>>);
+
+impl Log for Recorder {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn flush(&self) {}
+
+    fn log(&self, record: &Record) {
+        self.0
+            .lock()
+            .unwrap()
+            .push((record.level(), record.args().to_string()));
+    }
+}
+
+static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+
+#[test]
+fn events() {
+    log::set_logger(&RECORDER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let d = tempfile::tempdir().unwrap();
+    let d = d.path().to_str().unwrap();
+    let tar = Tar::new(d.to_string() + "/archive.tar");
+    let events = || RECORDER.0.lock().unwrap().clone();
+
+    assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+    assert!(tar.extract(d.to_string() + "/output").is_ok());
+    assert_eq!(
+        tar.extract_members(d, &["does_not_exist.txt"]),
+        Err(ExitCode::DataErr)
+    );
+
+    for event in [
+        (Level::Debug, format!("{d}/archive.tar:  adding Cargo.toml")),
+        (Level::Debug, format!("{d}/archive.tar:  adding LICENSE")),
+        (Level::Info, format!("writing {d}/archive.tar")),
+        (
+            Level::Debug,
+            format!("{d}/archive.tar:  extracting {d}/output/LICENSE"),
+        ),
+        (
+            Level::Error,
+            format!("{d}/archive.tar:  does_not_exist.txt:  not found"),
+        ),
+    ] {
+        assert!(events().contains(&event), "{event:?}");
+    }
+
+    assert!(PathBuf::from(d.to_string() + "/output/LICENSE").exists());
+}
+
+/******************************************************************************/
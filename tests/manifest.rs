@@ -0,0 +1,171 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+mod digest {
+    mod display {
+        use aeruginous_tbr::Digest;
+
+        #[test]
+        fn blake3() {
+            assert_eq!(format!("{}", Digest::Blake3), "BLAKE3");
+        }
+
+        #[test]
+        fn sha256() {
+            assert_eq!(format!("{}", Digest::Sha256), "SHA-256");
+        }
+    }
+
+    mod from_str {
+        use aeruginous_tbr::Digest;
+        use std::str::FromStr;
+
+        #[test]
+        fn blake3() {
+            assert_eq!(Digest::from_str("b3").unwrap(), Digest::Blake3);
+            assert_eq!(Digest::from_str("blake3").unwrap(), Digest::Blake3);
+        }
+
+        #[test]
+        fn failure() {
+            assert_eq!(
+                Digest::from_str("md5").unwrap_err(),
+                "'md5' is not supported, yet"
+            );
+        }
+
+        #[test]
+        fn sha256() {
+            assert_eq!(Digest::from_str("sha-256").unwrap(), Digest::Sha256);
+            assert_eq!(Digest::from_str("sha256").unwrap(), Digest::Sha256);
+        }
+    }
+
+    mod hash {
+        use aeruginous_tbr::Digest;
+
+        #[test]
+        fn blake3() {
+            assert_eq!(
+                Digest::Blake3.hash("abc".as_bytes()).unwrap(),
+                "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+            );
+        }
+
+        #[test]
+        fn sha256() {
+            assert_eq!(
+                Digest::Sha256.hash("abc".as_bytes()).unwrap(),
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+            );
+        }
+    }
+}
+
+mod manifest {
+    use aeruginous_tbr::{Digest, Manifest, Tar};
+    use std::path::PathBuf;
+    use sysexits::ExitCode;
+    use tempfile::tempdir;
+
+    #[test]
+    fn read_failure() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+
+        std::fs::write(d.to_string() + "/SHA256SUMS", "no_separator\n")
+            .unwrap();
+
+        assert_eq!(
+            Manifest::read(d.to_string() + "/SHA256SUMS", Digest::Sha256),
+            Err(ExitCode::DataErr)
+        );
+    }
+
+    #[test]
+    fn read_write() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let mut manifest = Manifest::new(Digest::Sha256);
+
+        manifest.push("LICENSE", "0123".to_string());
+        manifest.push("src/lib.rs", "abcd".to_string());
+
+        assert!(manifest.write(d.to_string() + "/SHA256SUMS").is_ok());
+        assert_eq!(
+            std::fs::read_to_string(d.to_string() + "/SHA256SUMS").unwrap(),
+            "0123  LICENSE\nabcd  src/lib.rs\n"
+        );
+        assert_eq!(
+            Manifest::read(d.to_string() + "/SHA256SUMS", Digest::Sha256)
+                .unwrap(),
+            manifest
+        );
+    }
+
+    #[test]
+    fn tar_archive() {
+        let d = tempdir().unwrap();
+        let d = d.path().to_str().unwrap();
+        let tar = Tar::new(d.to_string() + "/archive.tar");
+
+        assert!(tar.add_files(&["Cargo.toml", "LICENSE"]).is_ok());
+
+        let manifest = tar.manifest(Digest::Blake3).unwrap();
+
+        assert_eq!(manifest.digest(), Digest::Blake3);
+        assert_eq!(
+            manifest.entries(),
+            [
+                (
+                    PathBuf::from("Cargo.toml"),
+                    Digest::Blake3
+                        .hash(std::fs::File::open("Cargo.toml").unwrap())
+                        .unwrap()
+                ),
+                (
+                    PathBuf::from("LICENSE"),
+                    Digest::Blake3
+                        .hash(std::fs::File::open("LICENSE").unwrap())
+                        .unwrap()
+                )
+            ]
+        );
+        assert!(manifest.verify(&manifest).is_ok());
+        assert!(manifest.verify_directory(".").is_ok());
+    }
+
+    #[test]
+    fn verify_failure() {
+        let mut expected = Manifest::new(Digest::Sha256);
+        let mut actual = Manifest::new(Digest::Sha256);
+
+        expected.push("LICENSE", "0123".to_string());
+        expected.push("README.md", "4567".to_string());
+        actual.push("LICENSE", "abcd".to_string());
+
+        assert_eq!(expected.verify(&actual), Err(ExitCode::DataErr));
+        assert_eq!(
+            expected.verify_directory("does_not_exist"),
+            Err(ExitCode::DataErr)
+        );
+    }
+}
+
+/******************************************************************************/
@@ -0,0 +1,73 @@
+/*********************** GNU General Public License 3.0 ***********************\
+|                                                                              |
+|  Copyright (C) 2024 Kevin Matthes                                            |
+|                                                                              |
+|  This program is free software: you can redistribute it and/or modify        |
+|  it under the terms of the GNU General Public License as published by        |
+|  the Free Software Foundation, either version 3 of the License, or           |
+|  (at your option) any later version.                                         |
+|                                                                              |
+|  This program is distributed in the hope that it will be useful,             |
+|  but WITHOUT ANY WARRANTY; without even the implied warranty of              |
+|  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the               |
+|  GNU General Public License for more details.                                |
+|                                                                              |
+|  You should have received a copy of the GNU General Public License           |
+|  along with this program.  If not, see <https://www.gnu.org/licenses/>.      |
+|                                                                              |
+\******************************************************************************/
+
+//! The temporary directory lives in a separate file system in this test binary,
+//! such as a tmpfs, in order to simulate a common CI setup.  This binary runs in
+//! its own process such that changing the environment does not affect others.
+
+use aeruginous_io::PathBufLikeReader;
+use aeruginous_tbr::Tar;
+use std::path::{Path, PathBuf};
+
+fn separate_temporary_directory() -> tempfile::TempDir {
+    let shm = Path::new("/dev/shm");
+
+    // Fall back to an unusable directory if there is no tmpfs.
+    std::env::set_var(
+        "TMPDIR",
+        if shm.is_dir() {
+            shm
+        } else {
+            Path::new("does_not_exist")
+        },
+    );
+
+    tempfile::tempdir_in(env!("CARGO_TARGET_TMPDIR")).unwrap()
+}
+
+#[test]
+fn update() {
+    let d = separate_temporary_directory();
+    let tar = Tar::new(d.path().join("archive.tar"));
+
+    assert!(tar.add_files(&["LICENSE"]).is_ok());
+    assert!(tar.add_files(&["Cargo.toml"]).is_ok());
+    assert!(tar.rename(&[("Cargo.toml", "manifest.toml")]).is_ok());
+    assert_eq!(
+        tar.list().unwrap(),
+        [PathBuf::from("manifest.toml"), PathBuf::from("LICENSE")]
+    );
+
+    #[cfg(feature = "zip")]
+    {
+        let zip = aeruginous_tbr::Zip::new(d.path().join("archive.zip"));
+
+        assert!(zip.add_files(&["LICENSE"]).is_ok());
+        assert!(zip.add_files(&["LICENSE", "Cargo.toml"]).is_ok());
+        assert!(zip.extract(d.path()).is_ok());
+    }
+
+    assert!(tar.extract(d.path().join("output")).is_ok());
+    assert_eq!(
+        "LICENSE".read_silently().unwrap(),
+        d.path().join("output/LICENSE").read_silently().unwrap()
+    );
+}
+
+/******************************************************************************/